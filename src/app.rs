@@ -1,15 +1,33 @@
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
+
+mod batch;
+mod compare_installs;
+mod file_list;
+mod forge_inspector;
+mod interrupted_shear;
+mod job_progress;
+mod my_installs;
+mod profiles;
+mod selection_editor;
+mod space_planner;
+mod streaming_install_viewer;
 
 use crate::{
-    helpers::{
-        delete_events_folder, delete_texture_files, delete_videos_folder,
-        get_shearing_features_availability, is_siege_running, run_shears_version_background_check,
-        write_streaminginstall,
-    },
+    categories::{CategoryAvailability, ShearPlan, find_category},
+    diskusage::{RECLAIMABLE_IS_ESTIMATE, format_reclaimable},
+    games::{GameDefinition, detect_game},
+    gamesettings::{GameSettingsFile, find_game_settings, set_texture_quality},
+    helpers::{format_timestamp, run_shears_version_background_check},
+    installs::InstallRegistry,
+    journal::Journal,
+    ledger::ShearLedger,
+    profiles::ShearProfile,
     settings::PersistentSettingsStorage,
+    sizecache::SizeCache,
     types::{
-        ForgeTextureQualityLevel, ShearsFolderState, ShearsModals, ShearsPage,
-        ShearsScanFolderState, ShearsUiState,
+        ShearingFeaturesAvailability, ShearsBatchState, ShearsCompareState, ShearsFolderState,
+        ShearsForgeInspectorState, ShearsJobKind, ShearsJobState, ShearsModals, ShearsPage,
+        ShearsPlannerState, ShearsScanFolderState, ShearsStreamingInstallState, ShearsUiState,
     },
 };
 
@@ -20,6 +38,17 @@ pub struct ShearsApp {
     ui_state: ShearsUiState,
     system_information: sysinfo::System,
     persistent_settings_storage: PersistentSettingsStorage,
+    game_definitions: Vec<GameDefinition>,
+    forge_inspector_state: ShearsForgeInspectorState,
+    streaming_install_state: ShearsStreamingInstallState,
+    job_state: ShearsJobState,
+    size_cache: std::sync::Arc<SizeCache>,
+    install_registry: InstallRegistry,
+    install_histories: BTreeMap<PathBuf, ShearLedger>, // ledgers of the installs on My installs
+    batch_state: ShearsBatchState,
+    profiles: Vec<ShearProfile>,
+    planner_state: ShearsPlannerState,
+    compare_state: ShearsCompareState,
 }
 
 impl ShearsApp {
@@ -42,16 +71,40 @@ impl ShearsApp {
             run_shears_version_background_check(true);
         }
 
-        Self {
+        let mut app = Self {
             system_information: sysinfo::System::new(),
             persistent_settings_storage: settings,
+            game_definitions: GameDefinition::load_all(),
+            size_cache: std::sync::Arc::new(SizeCache::load_or_default()),
+            install_registry: InstallRegistry::load_or_default(),
+            profiles: ShearProfile::load_all(),
             ..Self::default()
-        }
+        };
+        app.open_pending_journal();
+        app
     }
 
     pub fn set_folder(&mut self, folder: &std::path::Path) {
+        if self.job_state.is_running(ShearsJobKind::Shear) {
+            log::warn!("Ignoring {}, a shear is in progress", folder.display());
+            return;
+        }
+
         self.folder_state.siege_path = Some(folder.to_path_buf());
+        self.folder_state.game = detect_game(&self.game_definitions, folder);
+        log::info!("Detected game: {}", self.folder_state.game.name);
+        self.install_registry
+            .remember_opened(folder, &self.folder_state.game);
+        self.install_registry.save_to_file();
         self.refresh_feature_availablity();
+
+        self.folder_state.interrupted_journal = Journal::load(folder);
+        if self.folder_state.interrupted_journal.is_some() {
+            log::warn!("Found an interrupted shear in {}", folder.display());
+            *self
+                .ui_state
+                .get_modal_mut(ShearsModals::InterruptedShear as usize) = true;
+        }
     }
 
     pub fn show_scan_drives_page(&mut self) {
@@ -60,98 +113,52 @@ impl ShearsApp {
     }
 
     pub fn refresh_feature_availablity(&mut self) {
-        let siege_path =
-            self.folder_state.siege_path.as_ref().expect(
-                "ShearsApp.refresh_feature_availablity: Failed to get folder_state.siege_path",
-            );
-        self.folder_state.features_availability = get_shearing_features_availability(siege_path);
+        self.start_analysis_job();
+    }
 
-        self.ui_state.change_page(ShearsPage::FolderSelected);
+    fn apply_analysis(
+        &mut self,
+        features_availability: ShearingFeaturesAvailability,
+        game_settings: Vec<GameSettingsFile>,
+    ) {
+        self.folder_state.features_availability = features_availability;
+        self.folder_state.game_settings = game_settings;
+        self.ui_state.damage_acknowledged = false;
 
         // set the feature checkboxes accordingly
-        for quality_level in
-            ForgeTextureQualityLevel::Low as usize..=ForgeTextureQualityLevel::Ultra as usize
-        {
-            *self.ui_state.get_texture_checkbox_mut(quality_level) = self
-                .folder_state
-                .features_availability
-                .get_texture(quality_level)
-                .0;
-        }
-
-        self.folder_state
+        self.ui_state.checkboxes = self
+            .folder_state
             .features_availability
-            .get_texture_mut(ForgeTextureQualityLevel::Low as usize)
-            .0 = false; // let's avoid users making their games completely unplayable
+            .categories
+            .iter()
+            .map(CategoryAvailability::default_selection)
+            .collect();
+        self.apply_remembered_install();
 
-        self.ui_state.checkbox_videos = self.folder_state.features_availability.videos.0;
-        self.ui_state.checkbox_events = self.folder_state.features_availability.events.0;
+        self.on_selection_changed();
+    }
 
+    fn on_selection_changed(&mut self) {
         self.compute_possible_space_freed();
+        self.check_selected_plan();
     }
 
-    fn compute_possible_space_freed(&mut self) {
-        self.ui_state.label_possible_space_saved = 0;
-
-        for quality_level in
-            ForgeTextureQualityLevel::Medium as usize..=ForgeTextureQualityLevel::Ultra as usize
-        // the user is not able to remove low textures so we can just skip it here
-        {
-            if !self.ui_state.get_texture_checkbox(quality_level) {
-                self.ui_state.label_possible_space_saved += self
-                    .folder_state
-                    .features_availability
-                    .get_texture(quality_level)
-                    .1;
-            }
-        }
-
-        if !self.ui_state.checkbox_videos {
-            self.ui_state.label_possible_space_saved +=
-                self.folder_state.features_availability.videos.1;
-        }
-
-        if !self.ui_state.checkbox_events {
-            self.ui_state.label_possible_space_saved +=
-                self.folder_state.features_availability.events.1;
-        }
+    fn check_selected_plan(&mut self) {
+        let categories = &self.folder_state.features_availability.categories;
+        self.ui_state.plan_error = ShearPlan::new(categories, &self.ui_state.checkboxes)
+            .check_analyzed(categories)
+            .err();
     }
 
-    fn execute_shearing(&mut self) -> bool {
-        let mut min_texture_quality_level = ForgeTextureQualityLevel::Low;
-        for i in (ForgeTextureQualityLevel::Medium.convert_to_i32()
-            ..=ForgeTextureQualityLevel::Ultra.convert_to_i32())
-            .rev()
-        {
-            let level = ForgeTextureQualityLevel::convert_from_i32(i)
-                .expect("Failed to convert i32 to ForgeTextureQualityLevel");
-            if self.ui_state.get_texture_checkbox(level as usize) {
-                min_texture_quality_level = level;
-                break;
-            }
-        }
-
-        if let Some(path_str) = self.folder_state.siege_path.clone() {
-            let path = std::path::Path::new(&path_str);
-
-            delete_texture_files(path, &min_texture_quality_level);
-
-            if !self.ui_state.checkbox_videos {
-                delete_videos_folder(path);
-            }
-
-            if !self.ui_state.checkbox_events {
-                delete_events_folder(path);
-            }
-
-            write_streaminginstall(path).expect("Failed to write streaming install");
-
-            self.set_folder(path);
-
-            return true;
-        }
-
-        false
+    fn compute_possible_space_freed(&mut self) {
+        self.ui_state.label_possible_space_saved = self
+            .folder_state
+            .features_availability
+            .categories
+            .iter()
+            .zip(&self.ui_state.checkboxes)
+            .map(|(category, keep)| category.removed_size(keep))
+            .sum();
     }
 
     fn render_menu_bar(&mut self, ctx: &egui::Context) {
@@ -165,6 +172,21 @@ impl ShearsApp {
                         self.set_folder(&path);
                     }
 
+                    if ui.button("My installs").clicked() {
+                        log::info!("Clicked on `My installs` button");
+                        self.show_my_installs_page();
+                    }
+
+                    if ui.button("Space planner").clicked() {
+                        log::info!("Clicked on `Space planner` button");
+                        self.show_space_planner_page();
+                    }
+
+                    if ui.button("Compare installs").clicked() {
+                        log::info!("Clicked on `Compare installs` button");
+                        self.show_compare_installs_page();
+                    }
+
                     if ui.button("Settings").clicked() {
                         log::info!("Clicked on `Settings` button");
                         *self.ui_state.get_modal_mut(ShearsModals::Settings as usize) = true;
@@ -219,6 +241,14 @@ impl ShearsApp {
             ShearsPage::DiskScanSelect => self.render_disk_scan_select_page(ctx),
             ShearsPage::DiskScanInProgress => self.render_disk_scan_in_progress_page(ctx),
             ShearsPage::DiskScanComplete => self.render_disk_scan_complete_page(ctx),
+            ShearsPage::ForgeInspector => self.render_forge_inspector_page(ctx),
+            ShearsPage::StreamingInstallViewer => self.render_streaming_install_page(ctx),
+            ShearsPage::JobInProgress => self.render_job_progress_page(ctx),
+            ShearsPage::MyInstalls => self.render_my_installs_page(ctx),
+            ShearsPage::BatchSetup => self.render_batch_setup_page(ctx),
+            ShearsPage::BatchReport => self.render_batch_report_page(ctx),
+            ShearsPage::SpacePlanner => self.render_space_planner_page(ctx),
+            ShearsPage::CompareInstalls => self.render_compare_installs_page(ctx),
         }
     }
 
@@ -304,7 +334,8 @@ impl ShearsApp {
                 }
 
                 if let Some(mount_point) = selected_mount_point {
-                    self.scan_state.start_scan_thread(mount_point);
+                    self.scan_state
+                        .start_scan_thread(mount_point, self.game_definitions.clone());
                     self.ui_state.change_page(ShearsPage::DiskScanInProgress);
                 }
             });
@@ -329,6 +360,7 @@ impl ShearsApp {
                     match handle.join() {
                         Ok(result) => {
                             log::info!("Completed disk scan, found {} items", result.len());
+                            self.remember_found_installs(&result);
                             self.scan_state.scan_results = Some(result);
                             self.scan_state.update_disks();
                             self.scan_state.update_scan_length();
@@ -392,19 +424,46 @@ impl ShearsApp {
                 if let Some(folders) = &self.scan_state.scan_results {
                     ui.label(format!("{} results", folders.len()));
 
+                    // ticked results can be sheared together in one batch
+                    let selected_folders = &mut self.batch_state.selected_folders;
                     for folder in folders {
-                        if ui.button(folder.to_string_lossy()).clicked() {
-                            selected_folder = Some(folder.clone());
-                        }
+                        ui.horizontal(|ui| {
+                            let mut selected = selected_folders.contains(folder);
+                            if ui.checkbox(&mut selected, "").changed() {
+                                if selected {
+                                    selected_folders.insert(folder.clone());
+                                } else {
+                                    selected_folders.remove(folder);
+                                }
+                            }
+
+                            if ui.button(folder.to_string_lossy()).clicked() {
+                                selected_folder = Some(folder.clone());
+                            }
+                        });
                     }
+
+                    selected_folders.retain(|folder| folders.contains(folder));
                 } else {
                     // somehow here without the thread result, fallback to main page
                     log::warn!("going back");
                     self.ui_state.reset_pages();
                 }
 
+                let show_batch_setup = ui
+                    .add_enabled(
+                        !self.batch_state.selected_folders.is_empty(),
+                        egui::Button::new(format!(
+                            "Shear {} selected installs...",
+                            self.batch_state.selected_folders.len()
+                        )),
+                    )
+                    .clicked();
+
                 if let Some(folder) = &selected_folder {
                     self.set_folder(folder);
+                } else if show_batch_setup {
+                    self.ui_state.change_page(ShearsPage::BatchSetup);
                 }
             });
     }
@@ -418,6 +477,9 @@ impl ShearsApp {
             };
 
             if ui.button(string).clicked() {
+                if self.job_state.is_running(ShearsJobKind::Analysis) {
+                    self.job_state.abandon();
+                }
                 self.ui_state.go_back();
             }
         });
@@ -430,6 +492,10 @@ impl ShearsApp {
             );
         }
 
+        if self.job_state.is_running(ShearsJobKind::Analysis) {
+            return true;
+        }
+
         if !self.folder_state.features_availability.has_forge_files {
             ui.label(egui::RichText::new("Folder does not contain FORGE files. Make sure you selected the correct folder.").color(egui::Color32::LIGHT_RED));
             return false;
@@ -438,195 +504,399 @@ impl ShearsApp {
         true
     }
 
-    fn validate_ui_state_checkbox(&mut self, level: ForgeTextureQualityLevel) {
-        if level == ForgeTextureQualityLevel::Low {
-            // this function will never be called with the low textures option
+    fn render_folder_selected_page_shear_state(&self, ui: &mut egui::Ui) {
+        let shear_state = &self.folder_state.shear_state;
+        ui.label(egui::RichText::new(shear_state.summary()).strong());
+
+        if !shear_state.missing.is_empty() {
+            ui.label(format!("Missing: {}", shear_state.missing.join(", ")));
+        }
+        for inconsistency in &shear_state.inconsistencies {
+            ui.label(egui::RichText::new(inconsistency).color(ui.visuals().warn_fg_color));
+        }
+        self.render_folder_selected_page_redownload(ui);
+
+        let history = &shear_state.ledger.shears;
+        if history.is_empty() {
             return;
         }
 
-        let level_idx = level as usize;
-        let checkboxes = &mut self.ui_state.checkbox_textures;
+        // newest first, read from the ledger inside the install
+        ui.collapsing(format!("Shear history ({})", history.len()), |ui| {
+            for (index, entry) in history.iter().enumerate().rev() {
+                let categories: Vec<&str> = entry
+                    .categories
+                    .iter()
+                    .map(|id| find_category(id).map_or(id.as_str(), |category| category.name()))
+                    .collect();
+                let profile = entry
+                    .profile
+                    .as_ref()
+                    .map(|profile| format!(", profile \"{profile}\""))
+                    .unwrap_or_default();
+
+                ui.label(format!(
+                    "{} with Shears {}{profile}: {} removed, {} freed",
+                    format_timestamp(entry.date),
+                    entry.version,
+                    categories.join(", "),
+                    format_reclaimable(entry.freed)
+                ));
+                egui::CollapsingHeader::new(format!("{} files", entry.files.len()))
+                    .id_salt(("shear_history_files", index))
+                    .show(ui, |ui| {
+                        for file in &entry.files {
+                            ui.label(
+                                egui::RichText::new(file.path.display().to_string()).monospace(),
+                            );
+                        }
+                    });
+            }
+        });
+    }
 
-        let ultra_idx = ForgeTextureQualityLevel::Ultra as usize;
-        if level_idx < ultra_idx {
-            checkboxes
-                .get_mut((level_idx + 1)..=ultra_idx)
-                .expect("Out of bounds error")
-                .fill(false);
+    fn render_folder_selected_page_damage_warning(&mut self, ui: &mut egui::Ui) {
+        let damaged_forges = &self.folder_state.features_availability.damaged_forges;
+        if damaged_forges.is_empty() {
+            return;
         }
 
-        if *checkboxes.get(level_idx).expect("Out of bounds error") {
-            let medium_idx = ForgeTextureQualityLevel::Medium as usize;
-            checkboxes
-                .get_mut(medium_idx..level_idx)
-                .expect("Out of bounds error")
-                .fill(true);
+        ui.group(|ui| {
+            ui.label(
+                egui::RichText::new(format!(
+                    "This installation looks damaged: {} forge files are invalid or truncated. Verify the game files before shearing.",
+                    damaged_forges.len()
+                ))
+                .color(egui::Color32::LIGHT_RED),
+            );
+
+            ui.collapsing("Damaged files", |ui| {
+                for (path, reason) in damaged_forges {
+                    let name = path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    ui.label(format!("{name}: {reason}"));
+                }
+            });
+
+            ui.checkbox(
+                &mut self.ui_state.damage_acknowledged,
+                "I understand, shear this installation anyway",
+            );
+        });
+    }
+
+    fn render_folder_selected_page_game_settings_warning(&mut self, ui: &mut egui::Ui) {
+        let Some(highest_kept) = self
+            .folder_state
+            .features_availability
+            .highest_texture_level()
+        else {
+            return;
+        };
+        let Some(definition) = &self.folder_state.game.settings else {
+            return;
+        };
+
+        let mut refresh_settings = false;
+        for settings in &self.folder_state.game_settings {
+            let Some(configured) = settings.texture_quality else {
+                continue;
+            };
+            if configured <= highest_kept {
+                continue;
+            }
+
+            ui.group(|ui| {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "The game is set to {configured} textures, but this installation only has up to {highest_kept}."
+                    ))
+                    .color(ui.visuals().warn_fg_color),
+                );
+                ui.label(egui::RichText::new(settings.path.display().to_string()).small());
+
+                if ui
+                    .button(format!("Set texture quality to {highest_kept}"))
+                    .clicked()
+                {
+                    match set_texture_quality(settings, definition, highest_kept) {
+                        Ok(()) => log::info!("Updated {}", settings.path.display()),
+                        Err(e) => {
+                            native_dialog::DialogBuilder::message()
+                                .set_level(native_dialog::MessageLevel::Error)
+                                .set_title("Error")
+                                .set_text(format!("Failed to update the game settings: {e}"))
+                                .alert()
+                                .show()
+                                .expect("Failed to show dialog");
+                        }
+                    }
+                    refresh_settings = true;
+                }
+            });
         }
+
+        if refresh_settings && let Some(siege_path) = &self.folder_state.siege_path {
+            self.folder_state.game_settings = find_game_settings(siege_path, definition);
+        }
+    }
+
+    // what the features group looks like while the analysis is still running
+    fn render_folder_selected_page_analysis_placeholder(&self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.heading(egui::RichText::new("Features"));
+                ui.spinner();
+            });
+            ui.label(
+                egui::RichText::new(self.job_state.progress.current())
+                    .small()
+                    .monospace(),
+            );
+
+            for category in self.folder_state.game.shear_categories() {
+                if category.is_experimental()
+                    && !self
+                        .persistent_settings_storage
+                        .enable_experimental_features
+                {
+                    continue;
+                }
+
+                ui.separator();
+                ui.label(
+                    egui::RichText::new(format!("{} (measuring...)", category.name())).strong(),
+                );
+            }
+        });
+    }
+
+    fn render_unreadable_entries(ui: &mut egui::Ui, category: &CategoryAvailability) {
+        ui.label(
+            egui::RichText::new(
+                "Partially measured: some entries could not be read, the real size is likely larger.",
+            )
+            .color(ui.visuals().warn_fg_color),
+        );
+
+        ui.collapsing(
+            format!("Unreadable entries ({})", category.category.name()),
+            |ui| {
+                for item in &category.items {
+                    for (path, reason) in &item.unreadable {
+                        ui.label(
+                            egui::RichText::new(format!("{}: {reason}", path.display()))
+                                .small()
+                                .monospace(),
+                        );
+                    }
+                }
+            },
+        );
     }
 
     fn render_folder_selected_page_available_features(&mut self, ui: &mut egui::Ui) {
-        // the texture checkboxes make sure you cant select for example, high without low, and so on up to ultra
         ui.group(|ui| {
             ui.horizontal(|ui| {
                 ui.heading(egui::RichText::new("Features"));
                 if ui.button("Refresh").clicked() {
+                    // measures everything again, files rewritten in place don't show up in the cache
+                    if let Some(siege_path) = &self.folder_state.siege_path {
+                        self.size_cache.forget(siege_path);
+                    }
                     self.refresh_feature_availablity();
                 }
+                if ui.button("Inspect forges").clicked() {
+                    self.show_forge_inspector_page();
+                }
+                if ui.button("Chunk mappings").clicked() {
+                    self.show_streaming_install_page();
+                }
             });
             ui.label("Choose what you want to keep");
 
-            ui.separator();
+            let mut selection_changed = false;
+            let advanced_selection = self.persistent_settings_storage.enable_advanced_selection;
 
-            for quality_level in
-                ForgeTextureQualityLevel::Low as usize..=ForgeTextureQualityLevel::Ultra as usize
+            for (category_index, category) in self
+                .folder_state
+                .features_availability
+                .categories
+                .iter()
+                .enumerate()
             {
-                let forge_texture_quality_level =
-                    ForgeTextureQualityLevel::convert_from_i32(quality_level as i32)
-                        .expect("Failed to convert i32 to ForgeTextureQualityLevel");
-
-                ui.add_enabled_ui(
-                    self.folder_state
-                        .features_availability
-                        .get_texture(quality_level)
-                        .0,
-                    |ui| {
-                        if ui
-                            .checkbox(
-                                self.ui_state
-                                    .checkbox_textures
-                                    .get_mut(quality_level)
-                                    .expect("Out of bounds error"),
-                                format!(
-                                    "{} Textures ({})",
-                                    forge_texture_quality_level,
-                                    humansize::format_size(
-                                        self.folder_state
-                                            .features_availability
-                                            .get_texture(quality_level)
-                                            .1,
-                                        humansize::WINDOWS
-                                    )
-                                ),
-                            )
-                            .clicked()
-                        {
-                            self.validate_ui_state_checkbox(forge_texture_quality_level);
-                            self.compute_possible_space_freed();
-                        }
-                    },
+                if category.items.is_empty() {
+                    continue;
+                }
+
+                if category.category.is_experimental()
+                    && !self
+                        .persistent_settings_storage
+                        .enable_experimental_features
+                {
+                    continue;
+                }
+
+                ui.separator();
+
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} ({})",
+                        category.category.name(),
+                        category.total_size()
+                    ))
+                    .strong(),
                 );
+
+                if category.is_partially_measured() {
+                    Self::render_unreadable_entries(ui, category);
+                }
+
+                let keep = self.ui_state.get_category_checkboxes_mut(category_index);
+                for (item_index, item) in category.items.iter().enumerate() {
+                    let mut label = format!("{} ({})", item.label, item.size);
+                    if item.is_partially_measured() {
+                        label.push_str(" [PARTIALLY MEASURED]");
+                    }
+                    if category.category.is_experimental() {
+                        label.push_str(" [EXPERIMENTAL]");
+                    }
+
+                    ui.add_enabled_ui(
+                        category.is_item_enabled(item_index, advanced_selection),
+                        |ui| {
+                            if ui
+                                .checkbox(
+                                    keep.get_mut(item_index).expect("Out of bounds error"),
+                                    label,
+                                )
+                                .clicked()
+                            {
+                                category.validate_selection(keep, item_index, advanced_selection);
+                                selection_changed = true;
+                            }
+                        },
+                    );
+                }
             }
 
-            ui.separator();
+            if selection_changed {
+                self.on_selection_changed();
+            }
 
-            ui.add_enabled_ui(self.folder_state.features_availability.videos.0, |ui| {
-                if ui
-                    .checkbox(
-                        &mut self.ui_state.checkbox_videos,
-                        format!(
-                            "Videos ({})",
-                            humansize::format_size(
-                                self.folder_state.features_availability.videos.1,
-                                humansize::WINDOWS
-                            )
-                        ),
+            if let Some(reason) = &self.ui_state.plan_error {
+                ui.label(egui::RichText::new(reason).color(egui::Color32::LIGHT_RED));
+            }
+        });
+    }
+
+    fn render_folder_selected_page_shear_button(&mut self, ui: &mut egui::Ui) {
+        let can_shear = self
+            .folder_state
+            .features_availability
+            .damaged_forges
+            .is_empty()
+            || self.ui_state.damage_acknowledged;
+        let can_shear = can_shear
+            && self.ui_state.plan_error.is_none()
+            && self.folder_state.interrupted_journal.is_none()
+            && !self.job_state.is_running(ShearsJobKind::Analysis);
+
+        if self.folder_state.interrupted_journal.is_some() {
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new(
+                        "This install has an interrupted shear, resume or roll it back first.",
                     )
-                    .clicked()
-                {
-                    self.compute_possible_space_freed();
+                    .color(ui.visuals().warn_fg_color),
+                );
+                if ui.button("Resume or roll back...").clicked() {
+                    *self
+                        .ui_state
+                        .get_modal_mut(ShearsModals::InterruptedShear as usize) = true;
                 }
             });
+        }
 
-            if self
-                .persistent_settings_storage
-                .enable_experimental_features
-            {
-                ui.add_enabled_ui(self.folder_state.features_availability.events.0, |ui| {
-                    if ui
-                        .checkbox(
-                            &mut self.ui_state.checkbox_events,
-                            format!(
-                                "Event files ({}) [EXPERIMENTAL]",
-                                humansize::format_size(
-                                    self.folder_state.features_availability.events.1,
-                                    humansize::WINDOWS
-                                )
-                            ),
-                        )
-                        .clicked()
-                    {
-                        self.compute_possible_space_freed();
-                    }
-                });
-            }
-        });
+        let confirmation_text = "Are you sure you want to continue? This change is permanent and cannot be undone. After proceeding you must verify your installation and re-download any affected files.";
+
+        if !ui
+            .add_enabled(can_shear, egui::Button::new("Shear!"))
+            .clicked()
+            || !native_dialog::DialogBuilder::message()
+                .set_level(native_dialog::MessageLevel::Warning)
+                .set_title("Read before proceeding")
+                .set_text(confirmation_text)
+                .confirm()
+                .show()
+                .expect("Failed to show dialog")
+        {
+            return;
+        }
+
+        if self
+            .folder_state
+            .game
+            .is_running(&mut self.system_information)
+        {
+            native_dialog::DialogBuilder::message()
+                .set_level(native_dialog::MessageLevel::Error)
+                .set_title("Error")
+                .set_text(format!(
+                    "{} is currently running! Please close it before shearing.",
+                    self.folder_state.game.name
+                ))
+                .alert()
+                .show()
+                .expect("Failed to show dialog");
+            return;
+        }
+
+        self.start_shear_job();
     }
 
     fn render_folder_selected_page(&mut self, ctx: &egui::Context) {
         egui::CentralPanel::default()
             .frame(egui::Frame::central_panel(&ctx.style()))
             .show(ctx, |ui| {
-                ui.horizontal_wrapped(|ui| {
-                    ui.vertical(|ui| {
-                        if !self.render_folder_selected_page_header(ui) {
-                            return;
-                        }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    if !self.render_folder_selected_page_header(ui) {
+                        return;
+                    }
 
+                    if self.job_state.is_running(ShearsJobKind::Analysis) {
+                        self.render_folder_selected_page_analysis_placeholder(ui);
+                        ui.label("Space saved: measuring...");
+                        ctx.request_repaint();
+                    } else {
+                        self.render_folder_selected_page_shear_state(ui);
+                        self.render_folder_selected_page_damage_warning(ui);
+                        self.render_folder_selected_page_game_settings_warning(ui);
+                        self.render_folder_selected_page_profiles(ui);
                         self.render_folder_selected_page_available_features(ui);
 
-                        ui.label(format!(
-                            "Space saved: {}",
-                            humansize::format_size(
-                                self.ui_state.label_possible_space_saved,
-                                humansize::WINDOWS
-                            )
-                        ));
-
-                        if ui.button("Shear!").clicked()
-                            && native_dialog::DialogBuilder::message()
-                            .set_level(native_dialog::MessageLevel::Warning)
-                            .set_title("Read before proceeding")
-                            .set_text("Are you sure you want to continue? This change is permanent and cannot be undone. After proceeding you must verify your installation and re-download any affected files.")
-                            .confirm()
-                            .show()
-                            .expect("Failed to show dialog")
-                        {
-                            if is_siege_running(&mut self.system_information) {
-                                native_dialog::DialogBuilder::message()
-                                    .set_level(native_dialog::MessageLevel::Error)
-                                    .set_title("Error")
-                                    .set_text("Rainbow Six Siege is currently running! Please close it before shearing.")
-                                    .alert()
-                                    .show()
-                                    .expect("Failed to show dialog");
-                                return;
-                            }
-                            if self.execute_shearing() {
-                                let message = self.folder_state.siege_path.as_ref()
-                                    .map(|path| format!("\"{}\" has been successfully sheared.", path.display()))
-                                    .unwrap_or_else(|| "The Siege folder has been successfully sheared.".to_owned());
-
-                                native_dialog::DialogBuilder::message()
-                                    .set_level(native_dialog::MessageLevel::Info)
-                                    .set_title("Success")
-                                    .set_text(&message)
-                                    .alert()
-                                    .show()
-                                    .expect("Failed to show dialog");
-                            } else {
-                                native_dialog::DialogBuilder::message()
-                                    .set_level(native_dialog::MessageLevel::Error)
-                                    .set_title("Failure")
-                                    .set_text("Shearing failed.")
-                                    .alert()
-                                    .show()
-                                    .expect("Failed to show dialog");
-                            }
+                        let space_saved = self.ui_state.label_possible_space_saved;
+                        let label = ui.label(format!("Space saved: {space_saved}"));
+                        if RECLAIMABLE_IS_ESTIMATE {
+                            label.on_hover_text("An estimate: the reclaimable size assumes 4 KiB clusters, and hard linked files can't be detected on Windows");
+                        }
+                        if space_saved.linked_files > 0 {
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "{} of the selected files are hard links and won't free any space.",
+                                    space_saved.linked_files
+                                ))
+                                .color(ui.visuals().warn_fg_color),
+                            );
                         }
+                        self.render_folder_selected_page_selection_export(ui);
                     }
-                );
+
+                    self.render_folder_selected_page_shear_button(ui);
+                });
             });
-        });
     }
 
     fn render_modals(&mut self, ctx: &egui::Context) -> bool {
@@ -667,6 +937,8 @@ impl ShearsApp {
             }
         }
 
+        self.render_interrupted_shear_modal(ctx);
+
         if self.ui_state.get_modal(ShearsModals::Settings as usize) {
             let modal = egui::Modal::new(egui::Id::new("ModalSettings")).show(ctx, |ui| {
                 ui.set_width(400.0);
@@ -691,6 +963,11 @@ impl ShearsApp {
                             .enable_experimental_features,
                         "Enable experimental features",
                     );
+
+                    ui.checkbox(
+                        &mut self.persistent_settings_storage.enable_advanced_selection,
+                        "Allow any combination of texture tiers (checked for compatibility before shearing)",
+                    );
                 });
 
                 if ui.button("Close").clicked() {
@@ -842,6 +1119,7 @@ impl ShearsApp {
 
 impl eframe::App for ShearsApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.poll_job();
         self.render_menu_bar(ctx);
         self.render_main_content(ctx);
 
@@ -0,0 +1,285 @@
+use crate::{
+    app::{ShearsApp, selection_editor::render_selection_editor},
+    batch::{BatchStatus, run_batch},
+    categories::{ShearCategory, ShearSelection},
+    diskusage::format_reclaimable,
+    games::{GameDefinition, detect_game},
+    journal::Journal,
+    types::{ShearsJobKind, ShearsJobResult, ShearsPage},
+};
+
+impl ShearsApp {
+    fn batch_games<'a>(
+        &self,
+        folders: impl IntoIterator<Item = &'a std::path::PathBuf>,
+    ) -> Vec<GameDefinition> {
+        let mut games: Vec<GameDefinition> = Vec::new();
+        for folder in folders {
+            let game = detect_game(&self.game_definitions, folder);
+            if !games.iter().any(|known| known.id == game.id) {
+                games.push(game);
+            }
+        }
+        games
+    }
+
+    // every category any of the selected installs can have, in the order of their game definitions
+    fn batch_categories(&self) -> Vec<&'static dyn ShearCategory> {
+        let mut categories: Vec<&'static dyn ShearCategory> = Vec::new();
+        for game in self.batch_games(&self.batch_state.selected_folders) {
+            for category in game.shear_categories() {
+                if !categories.iter().any(|known| known.id() == category.id()) {
+                    categories.push(category);
+                }
+            }
+        }
+        categories
+    }
+
+    fn start_batch_job(
+        &mut self,
+        jobs: Vec<(std::path::PathBuf, ShearSelection)>,
+        profile: Option<String>,
+        return_page: ShearsPage,
+    ) {
+        let definitions = self.game_definitions.clone();
+        let size_cache = self.size_cache.clone();
+        let advanced_selection = self.persistent_settings_storage.enable_advanced_selection;
+
+        for (folder, _) in &jobs {
+            self.persistent_settings_storage.add_pending_journal(folder);
+        }
+
+        self.batch_state.return_page = return_page;
+        self.job_state
+            .start_job(ShearsJobKind::Shear, move |progress| {
+                let results = run_batch(
+                    &jobs,
+                    advanced_selection,
+                    profile.as_deref(),
+                    &definitions,
+                    &size_cache,
+                    progress,
+                );
+                size_cache.save_to_file();
+                ShearsJobResult::Batch { results }
+            });
+        self.show_job_progress_page();
+    }
+
+    // asks for confirmation and makes sure none of the games are running first
+    pub(super) fn confirm_and_start_batch_job(
+        &mut self,
+        jobs: Vec<(std::path::PathBuf, ShearSelection)>,
+        profile: Option<String>,
+        return_page: ShearsPage,
+    ) -> bool {
+        let confirmation_text = format!(
+            "Are you sure you want to continue? {} installs will be sheared, this change is permanent and cannot be undone. After proceeding you must verify each installation and re-download any affected files.",
+            jobs.len()
+        );
+
+        if !native_dialog::DialogBuilder::message()
+            .set_level(native_dialog::MessageLevel::Warning)
+            .set_title("Read before proceeding")
+            .set_text(confirmation_text)
+            .confirm()
+            .show()
+            .expect("Failed to show dialog")
+        {
+            return false;
+        }
+
+        if let Some(game) = self
+            .batch_games(jobs.iter().map(|(folder, _)| folder))
+            .into_iter()
+            .find(|game| game.is_running(&mut self.system_information))
+        {
+            native_dialog::DialogBuilder::message()
+                .set_level(native_dialog::MessageLevel::Error)
+                .set_title("Error")
+                .set_text(format!(
+                    "{} is currently running! Please close it before shearing.",
+                    game.name
+                ))
+                .alert()
+                .show()
+                .expect("Failed to show dialog");
+            return false;
+        }
+
+        self.start_batch_job(jobs, profile, return_page);
+        true
+    }
+
+    pub(super) fn finish_batch_job(&mut self, results: Vec<crate::batch::BatchInstallResult>) {
+        for result in &results {
+            if !Journal::exists(&result.folder) {
+                self.persistent_settings_storage
+                    .remove_pending_journal(&result.folder);
+            }
+            self.refresh_install_history(&result.folder);
+        }
+
+        self.batch_state.report = results;
+        self.ui_state.replace_page(ShearsPage::BatchReport);
+    }
+
+    pub(super) fn render_batch_setup_page(&mut self, ctx: &egui::Context) {
+        egui::CentralPanel::default()
+            .frame(egui::Frame::central_panel(&ctx.style()))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Back").clicked() {
+                        self.ui_state.go_back();
+                    }
+                });
+
+                ui.heading("Batch shear");
+                ui.label(format!(
+                    "{} installs will be analyzed and sheared one after another:",
+                    self.batch_state.selected_folders.len()
+                ));
+                for folder in &self.batch_state.selected_folders {
+                    ui.label(egui::RichText::new(folder.display().to_string()).monospace());
+                }
+
+                let categories = self.batch_categories();
+                egui::ScrollArea::vertical()
+                    .max_height(ui.available_height() - 40.0)
+                    .show(ui, |ui| {
+                        ui.group(|ui| {
+                            ui.heading("Features");
+                            if !self.profiles.is_empty() {
+                                ui.label("Apply a profile");
+                                if let Some(profile) = self.render_profile_buttons(ui) {
+                                    self.batch_state.selection = self.profile_selection(&profile);
+                                    self.batch_state.profile = Some(profile.name);
+                                }
+                            }
+                            ui.label("Choose what you want to keep in every install");
+
+                            render_selection_editor(
+                                ui,
+                                &categories,
+                                &mut self.batch_state.selection,
+                                self.persistent_settings_storage
+                                    .enable_experimental_features,
+                                self.persistent_settings_storage.enable_advanced_selection,
+                            );
+                        });
+                    });
+
+                if !ui
+                    .add_enabled(
+                        !self.batch_state.selected_folders.is_empty(),
+                        egui::Button::new(format!(
+                            "Shear {} installs!",
+                            self.batch_state.selected_folders.len()
+                        )),
+                    )
+                    .clicked()
+                {
+                    return;
+                }
+
+                let jobs = self
+                    .batch_state
+                    .selected_folders
+                    .iter()
+                    .map(|folder| (folder.clone(), self.batch_state.selection.clone()))
+                    .collect();
+                let profile = self.batch_state.profile.clone();
+                self.confirm_and_start_batch_job(jobs, profile, ShearsPage::DiskScanComplete);
+            });
+    }
+
+    pub(super) fn render_batch_report_page(&mut self, ctx: &egui::Context) {
+        egui::CentralPanel::default()
+            .frame(egui::Frame::central_panel(&ctx.style()))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let return_page = self.batch_state.return_page;
+                    let return_text = match return_page {
+                        ShearsPage::SpacePlanner => "Back to space planner",
+                        ShearsPage::CompareInstalls => "Back to comparison",
+                        _ => "Back to scan results",
+                    };
+                    if ui.button(return_text).clicked() {
+                        self.ui_state.change_page_no_history(return_page);
+                    }
+                    if ui.button("Back to Main Menu").clicked() {
+                        self.ui_state.change_page_no_history(ShearsPage::MainPage);
+                    }
+                });
+
+                let report = &self.batch_state.report;
+                let total_freed: u64 = report.iter().map(|result| result.freed).sum();
+                let failed = report.iter().filter(|result| !result.is_success()).count();
+
+                ui.heading("Batch report");
+                ui.label(format!(
+                    "Space reclaimed across {} installs: {}",
+                    report.len(),
+                    format_reclaimable(total_freed)
+                ));
+                if failed > 0 {
+                    ui.label(
+                        egui::RichText::new(format!("{failed} installs need attention"))
+                            .color(egui::Color32::LIGHT_RED),
+                    );
+                }
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for result in report {
+                        ui.group(|ui| {
+                            ui.label(
+                                egui::RichText::new(result.folder.display().to_string())
+                                    .monospace(),
+                            );
+
+                            let (status, color) = match &result.status {
+                                BatchStatus::Sheared(outcome) if outcome.cancelled => (
+                                    "Cancelled, open the install to resume or roll back".to_owned(),
+                                    ui.visuals().warn_fg_color,
+                                ),
+                                BatchStatus::Sheared(outcome) if !outcome.failures.is_empty() => (
+                                    format!(
+                                        "Nothing was deleted, these could not be moved aside:\n{}\nOpen the install to resume or roll back",
+                                        outcome.failures.join("\n")
+                                    ),
+                                    egui::Color32::LIGHT_RED,
+                                ),
+                                BatchStatus::Sheared(_) => (
+                                    format!("Sheared, {} freed", format_reclaimable(result.freed)),
+                                    egui::Color32::LIGHT_GREEN,
+                                ),
+                                BatchStatus::NothingToRemove => {
+                                    ("Nothing to remove".to_owned(), ui.visuals().text_color())
+                                }
+                                BatchStatus::Skipped => (
+                                    "Skipped, the batch was cancelled".to_owned(),
+                                    ui.visuals().warn_fg_color,
+                                ),
+                                BatchStatus::Failed(reason) => {
+                                    (reason.clone(), egui::Color32::LIGHT_RED)
+                                }
+                            };
+                            ui.label(egui::RichText::new(status).color(color));
+
+                            if !result.removed_items.is_empty() {
+                                ui.collapsing(
+                                    format!("{} items removed", result.removed_items.len()),
+                                    |ui| {
+                                        for removed in &result.removed_items {
+                                            ui.label(removed);
+                                        }
+                                    },
+                                );
+                            }
+                        });
+                    }
+                });
+            });
+    }
+}
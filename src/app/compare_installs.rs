@@ -0,0 +1,281 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    app::ShearsApp,
+    categories::ShearSelection,
+    compare::{DiffGroup, FileDiffKind, InstallComparison, compare_installs},
+    games::detect_game,
+    types::{ShearingFeaturesAvailability, ShearsJobKind, ShearsJobResult, ShearsPage},
+};
+
+// big groups like events can hold thousands of files, the rest is only counted
+const MAX_LISTED_FILES: usize = 1000;
+
+fn format_size(bytes: u64) -> String {
+    humansize::format_size(bytes, humansize::WINDOWS)
+}
+
+fn describe_group(group: &DiffGroup) -> String {
+    let (mut only_a, mut only_b, mut different) = (0, 0, 0);
+    for diff in &group.files {
+        match diff.kind {
+            FileDiffKind::OnlyInA(_) => only_a += 1,
+            FileDiffKind::OnlyInB(_) => only_b += 1,
+            FileDiffKind::SizeDiffers { .. } | FileDiffKind::ContentDiffers(_) => different += 1,
+        }
+    }
+
+    format!(
+        "{}: {only_a} only in A, {only_b} only in B, {different} different",
+        group.name
+    )
+}
+
+fn describe_diff(kind: FileDiffKind) -> String {
+    match kind {
+        FileDiffKind::OnlyInA(len) => format!("only in A ({})", format_size(len)),
+        FileDiffKind::OnlyInB(len) => format!("only in B ({})", format_size(len)),
+        FileDiffKind::SizeDiffers { a, b } => {
+            format!("size differs ({} vs {})", format_size(a), format_size(b))
+        }
+        FileDiffKind::ContentDiffers(len) => format!("content differs ({})", format_size(len)),
+    }
+}
+
+// what a match would remove, one "Category: item" per line
+fn describe_selection(
+    features: &ShearingFeaturesAvailability,
+    selection: &ShearSelection,
+) -> String {
+    features
+        .categories
+        .iter()
+        .flat_map(|availability| {
+            availability
+                .items
+                .iter()
+                .filter(|item| {
+                    item.is_available() && selection.is_removed(availability.category, &item.key)
+                })
+                .map(|item| format!("{}: {}", availability.category.name(), item.label))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl ShearsApp {
+    pub fn show_compare_installs_page(&mut self) {
+        self.ui_state.change_page(ShearsPage::CompareInstalls);
+    }
+
+    fn start_compare_job(&mut self, a: PathBuf, b: PathBuf) {
+        let categories = detect_game(&self.game_definitions, &a).shear_categories();
+        let compare_contents = self.compare_state.compare_contents;
+        let size_cache = self.size_cache.clone();
+
+        self.compare_state.comparison = None;
+        self.job_state
+            .start_job(ShearsJobKind::Compare, move |progress| {
+                let comparison =
+                    compare_installs(&a, &b, &categories, compare_contents, &size_cache, progress);
+                if !progress.is_cancelled() {
+                    size_cache.save_to_file();
+                }
+                ShearsJobResult::Compare { comparison }
+            });
+    }
+
+    pub(super) fn finish_compare_job(&mut self, comparison: InstallComparison) {
+        self.compare_state.comparison = Some(comparison);
+    }
+
+    fn install_name(&self, folder: &Path) -> String {
+        self.install_registry
+            .get(folder)
+            .map_or_else(|| folder.display().to_string(), |i| i.display_name())
+    }
+
+    fn render_compare_install_picker(&mut self, ui: &mut egui::Ui, is_a: bool) {
+        let folders: Vec<PathBuf> = self
+            .install_registry
+            .sorted()
+            .into_iter()
+            .map(|install| install.path.clone())
+            .filter(|path| path.is_dir())
+            .collect();
+        let names: Vec<String> = folders
+            .iter()
+            .map(|folder| self.install_name(folder))
+            .collect();
+
+        let (label, selected) = if is_a {
+            ("A", &mut self.compare_state.a)
+        } else {
+            ("B", &mut self.compare_state.b)
+        };
+
+        ui.horizontal(|ui| {
+            ui.label(format!("{label}:"));
+            let selected_text = selected
+                .as_ref()
+                .and_then(|folder| folders.iter().position(|known| known == folder))
+                .and_then(|index| names.get(index).cloned())
+                .unwrap_or_else(|| "Select an install".to_owned());
+
+            egui::ComboBox::from_id_salt(format!("compare_install_{label}"))
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for (folder, name) in folders.iter().zip(&names) {
+                        ui.selectable_value(selected, Some(folder.clone()), name)
+                            .on_hover_text(folder.display().to_string());
+                    }
+                });
+        });
+    }
+
+    // a batch of one, so the shear shows up in the report and the install history like any other
+    fn render_compare_match_buttons(&mut self, ui: &mut egui::Ui) {
+        let Some(comparison) = &self.compare_state.comparison else {
+            return;
+        };
+
+        let matches = [
+            (
+                &comparison.a,
+                "Shear A to match B",
+                &comparison.features_a,
+                &comparison.features_b,
+            ),
+            (
+                &comparison.b,
+                "Shear B to match A",
+                &comparison.features_b,
+                &comparison.features_a,
+            ),
+        ];
+
+        let mut job = None;
+        ui.horizontal(|ui| {
+            for (folder, text, from, to) in matches {
+                let selection = InstallComparison::match_selection(from, to);
+                let response = ui.add_enabled(
+                    selection != ShearSelection::default(),
+                    egui::Button::new(text),
+                );
+                if response.clicked() {
+                    job = Some((folder.clone(), selection));
+                } else {
+                    response.on_hover_text(describe_selection(from, &selection));
+                }
+            }
+        });
+
+        if let Some(job) = job
+            && self.confirm_and_start_batch_job(vec![job], None, ShearsPage::CompareInstalls)
+        {
+            self.compare_state.comparison = None;
+        }
+    }
+
+    fn render_comparison(&self, ui: &mut egui::Ui) {
+        let Some(comparison) = &self.compare_state.comparison else {
+            return;
+        };
+
+        ui.label(format!("A: {}", comparison.a.display()));
+        ui.label(format!("B: {}", comparison.b.display()));
+
+        if comparison.is_identical() {
+            ui.label(if comparison.compared_contents {
+                "The installs are identical."
+            } else {
+                "The installs have the same files with the same sizes."
+            });
+            return;
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for group in &comparison.groups {
+                ui.collapsing(describe_group(group), |ui| {
+                    for diff in group.files.iter().take(MAX_LISTED_FILES) {
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                egui::RichText::new(diff.path.display().to_string()).monospace(),
+                            );
+                            ui.label(describe_diff(diff.kind));
+                        });
+                    }
+                    if group.files.len() > MAX_LISTED_FILES {
+                        ui.label(format!(
+                            "... and {} more",
+                            group.files.len() - MAX_LISTED_FILES
+                        ));
+                    }
+                });
+            }
+        });
+    }
+
+    pub(super) fn render_compare_installs_page(&mut self, ctx: &egui::Context) {
+        egui::CentralPanel::default()
+            .frame(egui::Frame::central_panel(&ctx.style()))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Back").clicked() {
+                        if self.job_state.is_running(ShearsJobKind::Compare) {
+                            self.job_state.abandon();
+                        }
+                        self.ui_state.go_back();
+                    }
+                });
+
+                ui.heading("Compare installs");
+                self.render_compare_install_picker(ui, true);
+                self.render_compare_install_picker(ui, false);
+                ui.checkbox(
+                    &mut self.compare_state.compare_contents,
+                    "Also compare the contents of files with the same size (slow, reads both installs in full)",
+                );
+
+                if self.job_state.is_running(ShearsJobKind::Compare) {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(self.job_state.progress.stage());
+                    });
+                    ui.label(
+                        egui::RichText::new(self.job_state.progress.current())
+                            .small()
+                            .monospace(),
+                    );
+                    if ui.button("Cancel").clicked() {
+                        self.job_state.abandon();
+                    }
+                    ctx.request_repaint();
+                    return;
+                }
+
+                let pair = self
+                    .compare_state
+                    .a
+                    .clone()
+                    .zip(self.compare_state.b.clone())
+                    .filter(|(a, b)| a != b);
+                if ui
+                    .add_enabled(
+                        pair.is_some() && !self.job_state.is_running(ShearsJobKind::Shear),
+                        egui::Button::new("Compare"),
+                    )
+                    .clicked()
+                    && let Some((a, b)) = pair
+                {
+                    log::info!("Clicked on `Compare` button");
+                    self.start_compare_job(a, b);
+                    return;
+                }
+
+                ui.separator();
+                self.render_compare_match_buttons(ui);
+                self.render_comparison(ui);
+            });
+    }
+}
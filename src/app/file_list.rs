@@ -0,0 +1,100 @@
+use crate::{app::ShearsApp, categories::ShearPlan, filelist::DepotFileList};
+
+fn format_size(bytes: u64) -> String {
+    humansize::format_size(bytes, humansize::WINDOWS)
+}
+
+fn show_file_list_message(level: native_dialog::MessageLevel, text: &str) {
+    native_dialog::DialogBuilder::message()
+        .set_level(level)
+        .set_title("DepotDownloader file list")
+        .set_text(text)
+        .alert()
+        .show()
+        .expect("Failed to show dialog");
+}
+
+fn export_depot_file_list(list: &DepotFileList) {
+    let Some(path) = rfd::FileDialog::new()
+        .set_file_name("filelist.txt")
+        .add_filter("Text file", &["txt"])
+        .save_file()
+    else {
+        return;
+    };
+
+    match list.write(&path) {
+        Ok(()) => show_file_list_message(
+            native_dialog::MessageLevel::Info,
+            &format!(
+                "Saved {} entries to {}, about {} to re-download.\n\nPass it to DepotDownloader with `-filelist`, together with the app, depot and manifest of this version of the game and `-dir` pointing at this install.",
+                list.len(),
+                path.display(),
+                format_size(list.size())
+            ),
+        ),
+        Err(e) => show_file_list_message(
+            native_dialog::MessageLevel::Error,
+            &format!("Failed to save the file list: {e}"),
+        ),
+    }
+}
+
+impl ShearsApp {
+    // the ledger says what was removed, the list is worked out when the install is analyzed
+    pub(super) fn render_folder_selected_page_redownload(&self, ui: &mut egui::Ui) {
+        let list = &self.folder_state.shear_state.redownload;
+        if list.is_empty() {
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            if ui
+                .button("Export DepotDownloader file list...")
+                .on_hover_text("Lists the removed textures, videos and events that are still missing, so DepotDownloader only downloads those")
+                .clicked()
+            {
+                log::info!("Clicked on `Export DepotDownloader file list...` button");
+                export_depot_file_list(list);
+            }
+            ui.label(format!(
+                "{} files, about {} to re-download",
+                list.len(),
+                format_size(list.size())
+            ));
+        });
+    }
+
+    pub(super) fn render_folder_selected_page_selection_export(&self, ui: &mut egui::Ui) {
+        let Some(siege_path) = &self.folder_state.siege_path else {
+            return;
+        };
+
+        if !ui
+            .add_enabled(
+                self.ui_state.plan_error.is_none(),
+                egui::Button::new("Export file list of this selection..."),
+            )
+            .on_hover_text("Saves the textures, videos and events this selection removes as a DepotDownloader file list, to download them again later")
+            .clicked()
+        {
+            return;
+        }
+
+        log::info!("Clicked on `Export file list of this selection...` button");
+        let plan = ShearPlan::new(
+            &self.folder_state.features_availability.categories,
+            &self.ui_state.checkboxes,
+        );
+        let list = DepotFileList::from_plan(siege_path, &plan);
+        if list.is_empty() {
+            show_file_list_message(
+                native_dialog::MessageLevel::Info,
+                "Nothing in this selection can be downloaded again with DepotDownloader.",
+            );
+            return;
+        }
+
+        export_depot_file_list(&list);
+    }
+}
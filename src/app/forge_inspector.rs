@@ -0,0 +1,185 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    app::ShearsApp,
+    forge::{ForgeFile, list_forge_files, read_forge},
+    progress::JobProgress,
+    types::{ShearsForgeInspectorState, ShearsJobKind, ShearsJobResult, ShearsPage},
+};
+
+// reads the entry tables of every forge, which takes a while on a full install
+fn inspect_forges(
+    folder: &Path,
+    progress: &JobProgress,
+) -> Vec<(PathBuf, Result<ForgeFile, String>)> {
+    progress.set_stage("Reading forge files");
+
+    let mut forges = Vec::new();
+    for path in list_forge_files(folder) {
+        if progress.is_cancelled() {
+            break;
+        }
+        progress.set_current(path.display().to_string());
+
+        let forge = read_forge(&path).map_err(|e| e.to_string());
+        if let Err(e) = &forge {
+            log::warn!("{}: {e}", path.display());
+        }
+        forges.push((path, forge));
+    }
+    forges
+}
+
+impl ShearsApp {
+    pub fn show_forge_inspector_page(&mut self) {
+        let Some(siege_path) = self.folder_state.siege_path.clone() else {
+            return;
+        };
+
+        self.forge_inspector_state = ShearsForgeInspectorState::default();
+        self.job_state
+            .start_job(ShearsJobKind::InspectForges, move |progress| {
+                ShearsJobResult::InspectForges {
+                    forges: inspect_forges(&siege_path, progress),
+                }
+            });
+        self.ui_state.change_page(ShearsPage::ForgeInspector);
+    }
+
+    pub(super) fn finish_forge_inspection(
+        &mut self,
+        forges: Vec<(PathBuf, Result<ForgeFile, String>)>,
+    ) {
+        self.forge_inspector_state.forges = forges;
+    }
+
+    pub(super) fn render_forge_inspector_page(&mut self, ctx: &egui::Context) {
+        egui::CentralPanel::default()
+            .frame(egui::Frame::central_panel(&ctx.style()))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Back").clicked() {
+                        if self.job_state.is_running(ShearsJobKind::InspectForges) {
+                            self.job_state.abandon();
+                        }
+                        self.ui_state.go_back();
+                    }
+                });
+
+                if self.job_state.is_running(ShearsJobKind::InspectForges) {
+                    ui.heading("Forge inspector");
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(self.job_state.progress.stage());
+                    });
+                    ui.label(
+                        egui::RichText::new(self.job_state.progress.current())
+                            .small()
+                            .monospace(),
+                    );
+                    ctx.request_repaint();
+                    return;
+                }
+
+                let invalid_count = self
+                    .forge_inspector_state
+                    .forges
+                    .iter()
+                    .filter(|(_, forge)| forge.is_err())
+                    .count();
+
+                ui.heading("Forge inspector");
+                ui.label(format!(
+                    "{} forge files, {invalid_count} invalid or truncated",
+                    self.forge_inspector_state.forges.len()
+                ));
+
+                egui::ScrollArea::vertical()
+                    .id_salt("forge_list")
+                    .max_height(150.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for (index, (path, forge)) in
+                            self.forge_inspector_state.forges.iter().enumerate()
+                        {
+                            let name = path
+                                .file_name()
+                                .map(|name| name.to_string_lossy().into_owned())
+                                .unwrap_or_default();
+
+                            let text = if forge.is_ok() {
+                                egui::RichText::new(name)
+                            } else {
+                                egui::RichText::new(format!("{name} (invalid)"))
+                                    .color(egui::Color32::LIGHT_RED)
+                            };
+
+                            if ui
+                                .selectable_label(
+                                    self.forge_inspector_state.selected == Some(index),
+                                    text,
+                                )
+                                .clicked()
+                            {
+                                self.forge_inspector_state.selected = Some(index);
+                            }
+                        }
+                    });
+
+                ui.separator();
+
+                self.render_forge_inspector_details(ui);
+            });
+    }
+
+    fn render_forge_inspector_details(&self, ui: &mut egui::Ui) {
+        let Some((path, forge)) = self
+            .forge_inspector_state
+            .selected
+            .and_then(|index| self.forge_inspector_state.forges.get(index))
+        else {
+            ui.label("Select a forge file to see its entries.");
+            return;
+        };
+
+        ui.label(egui::RichText::new(path.display().to_string()).monospace());
+
+        let forge = match forge {
+            Ok(forge) => forge,
+            Err(e) => {
+                ui.label(egui::RichText::new(e).color(egui::Color32::LIGHT_RED));
+                return;
+            }
+        };
+
+        ui.label(format!(
+            "Version {}, {} entries, {} of {} in entries",
+            forge.version,
+            forge.entries.len(),
+            humansize::format_size(forge.total_entry_size(), humansize::WINDOWS),
+            humansize::format_size(forge.file_len, humansize::WINDOWS)
+        ));
+
+        egui::ScrollArea::vertical()
+            .id_salt("forge_entries")
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                egui::Grid::new("forge_entry_types")
+                    .striped(true)
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        ui.strong("Entry type");
+                        ui.strong("Count");
+                        ui.strong("Size");
+                        ui.end_row();
+
+                        for summary in forge.summarize_by_type() {
+                            ui.monospace(format!("0x{:08X}", summary.entry_type));
+                            ui.label(summary.count.to_string());
+                            ui.label(humansize::format_size(summary.size, humansize::WINDOWS));
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{app::ShearsApp, helpers::format_timestamp, journal::Journal, types::ShearsModals};
+
+impl ShearsApp {
+    // opens the first install that still has an unfinished shear journal from a previous run
+    pub(super) fn open_pending_journal(&mut self) {
+        let pending = &mut self.persistent_settings_storage.pending_journals;
+        let count = pending.len();
+        pending.retain(|folder| Journal::exists(folder));
+        if pending.len() != count {
+            self.persistent_settings_storage.save_to_file();
+        }
+
+        if let Some(folder) = self
+            .persistent_settings_storage
+            .pending_journals
+            .first()
+            .cloned()
+        {
+            self.set_folder(&folder);
+        }
+    }
+
+    fn roll_back_interrupted_shear(&mut self) {
+        let Some(siege_path) = self.folder_state.siege_path.clone() else {
+            return;
+        };
+        let Some(mut journal) = self.folder_state.interrupted_journal.take() else {
+            return;
+        };
+
+        match journal.roll_back() {
+            Ok(()) => {
+                self.persistent_settings_storage
+                    .remove_pending_journal(&siege_path);
+
+                native_dialog::DialogBuilder::message()
+                    .set_level(native_dialog::MessageLevel::Info)
+                    .set_title("Success")
+                    .set_text("The interrupted shear has been rolled back.")
+                    .alert()
+                    .show()
+                    .expect("Failed to show dialog");
+            }
+            Err(reason) => {
+                native_dialog::DialogBuilder::message()
+                    .set_level(native_dialog::MessageLevel::Error)
+                    .set_title("Failure")
+                    .set_text(&reason)
+                    .alert()
+                    .show()
+                    .expect("Failed to show dialog");
+            }
+        }
+
+        self.set_folder(&siege_path);
+    }
+
+    pub(super) fn render_interrupted_shear_modal(&mut self, ctx: &egui::Context) {
+        if !self
+            .ui_state
+            .get_modal(ShearsModals::InterruptedShear as usize)
+        {
+            return;
+        }
+
+        let Some(journal) = &self.folder_state.interrupted_journal else {
+            *self
+                .ui_state
+                .get_modal_mut(ShearsModals::InterruptedShear as usize) = false;
+            return;
+        };
+
+        let mut resume = false;
+        let mut roll_back = false;
+
+        let modal = egui::Modal::new(egui::Id::new("ModalInterruptedShear")).show(ctx, |ui| {
+            ui.set_width(400.0);
+
+            ui.heading("Interrupted shear");
+            if let Some(siege_path) = &self.folder_state.siege_path {
+                ui.label(egui::RichText::new(siege_path.display().to_string()).monospace());
+            }
+
+            ui.label(format!(
+                "A shear started on {} did not finish. {} of {} planned removals were already moved aside.",
+                format_timestamp(journal.started),
+                journal.staged_count(),
+                journal.entries.len()
+            ));
+
+            if !journal.can_roll_back() {
+                ui.label(
+                    egui::RichText::new(
+                        "The staged files were already being deleted, so the shear can only be resumed.",
+                    )
+                    .color(ui.visuals().warn_fg_color),
+                );
+            }
+
+            ui.horizontal(|ui| {
+                resume = ui.button("Resume").clicked();
+                roll_back = ui
+                    .add_enabled(journal.can_roll_back(), egui::Button::new("Roll back"))
+                    .clicked();
+
+                if ui.button("Decide later").clicked() {
+                    ui.close();
+                }
+            });
+        });
+
+        if resume || roll_back || modal.should_close() {
+            *self
+                .ui_state
+                .get_modal_mut(ShearsModals::InterruptedShear as usize) = false;
+        }
+
+        if resume {
+            self.start_resume_job();
+        } else if roll_back {
+            self.roll_back_interrupted_shear();
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use crate::{
+    app::ShearsApp,
+    categories::ShearPlan,
+    diskusage::format_reclaimable,
+    gamesettings::find_game_settings,
+    helpers::{get_available_space, get_shearing_features_availability},
+    journal::{Journal, JournalOutcome, resume_shear, shear},
+    progress::JobProgress,
+    shearstate::{ShearState, detect_shear_state},
+    types::{ShearingFeaturesAvailability, ShearsJobKind, ShearsJobResult, ShearsPage},
+};
+
+impl ShearsApp {
+    pub(super) fn show_job_progress_page(&mut self) {
+        if self.ui_state.get_page() != ShearsPage::FolderSelected
+            && self.ui_state.get_page() != ShearsPage::JobInProgress
+        {
+            self.ui_state.change_page(ShearsPage::FolderSelected);
+        }
+        self.ui_state.replace_page(ShearsPage::JobInProgress);
+    }
+
+    // the folder page stays usable while this runs and shows placeholders until the results are in
+    pub(super) fn start_analysis_job(&mut self) {
+        let Some(folder) = self.folder_state.siege_path.clone() else {
+            return;
+        };
+        let categories = self.folder_state.game.shear_categories();
+        let settings_definition = self.folder_state.game.settings.clone();
+        let size_cache = self.size_cache.clone();
+
+        self.folder_state.features_availability = ShearingFeaturesAvailability::default();
+        self.folder_state.game_settings = Vec::new();
+        self.folder_state.shear_state = ShearState::default();
+        self.ui_state.checkboxes = Vec::new();
+        self.ui_state.plan_error = None;
+        self.ui_state.applied_profile = None;
+
+        self.job_state
+            .start_job(ShearsJobKind::Analysis, move |progress| {
+                let features_availability =
+                    get_shearing_features_availability(&folder, &categories, &size_cache, progress);
+                let game_settings = settings_definition
+                    .map(|definition| find_game_settings(&folder, &definition))
+                    .unwrap_or_default();
+                let shear_state = detect_shear_state(&folder, &features_availability);
+
+                if !progress.is_cancelled() {
+                    size_cache.save_to_file();
+                }
+
+                ShearsJobResult::Analysis {
+                    folder,
+                    features_availability,
+                    game_settings,
+                    shear_state,
+                }
+            });
+
+        if self.ui_state.get_page() == ShearsPage::JobInProgress {
+            self.ui_state.replace_page(ShearsPage::FolderSelected);
+        } else if self.ui_state.get_page() != ShearsPage::FolderSelected {
+            self.ui_state.change_page(ShearsPage::FolderSelected);
+        }
+    }
+
+    fn spawn_shear_job(
+        &mut self,
+        folder: std::path::PathBuf,
+        shear_job: impl FnOnce(&std::path::Path, &JobProgress) -> Result<JournalOutcome, String>
+        + Send
+        + 'static,
+    ) {
+        self.persistent_settings_storage
+            .add_pending_journal(&folder);
+        self.job_state
+            .start_job(ShearsJobKind::Shear, move |progress| {
+                let available_before = get_available_space(&folder);
+                let result = shear_job(&folder, progress);
+                let available_space = available_before.zip(get_available_space(&folder));
+
+                ShearsJobResult::Shear {
+                    folder,
+                    result,
+                    available_space,
+                }
+            });
+        self.show_job_progress_page();
+    }
+
+    pub(super) fn start_shear_job(&mut self) {
+        let Some(folder) = self.folder_state.siege_path.clone() else {
+            return;
+        };
+        let game = self.folder_state.game.clone();
+        let plan = ShearPlan::new(
+            &self.folder_state.features_availability.categories,
+            &self.ui_state.checkboxes,
+        );
+        let profile = self.ui_state.applied_profile.clone();
+        self.remember_selection();
+
+        self.spawn_shear_job(folder, move |folder, progress| {
+            shear(folder, &game, &plan, profile, progress)
+        });
+    }
+
+    pub(super) fn start_resume_job(&mut self) {
+        let Some(folder) = self.folder_state.siege_path.clone() else {
+            return;
+        };
+        let game = self.folder_state.game.clone();
+        self.folder_state.interrupted_journal = None;
+
+        self.spawn_shear_job(folder, move |folder, progress| {
+            resume_shear(folder, &game, progress)
+        });
+    }
+
+    fn describe_available_space(available_space: Option<(u64, u64)>) -> String {
+        match available_space {
+            Some((before, after)) if after >= before => format!(
+                "Free space on the drive went from {} to {} (+{}).",
+                humansize::format_size(before, humansize::WINDOWS),
+                humansize::format_size(after, humansize::WINDOWS),
+                humansize::format_size(after - before, humansize::WINDOWS)
+            ),
+            Some(_) => {
+                "Free space on the drive did not grow, something else is writing to it.".to_owned()
+            }
+            None => "The change in free space on the drive could not be measured.".to_owned(),
+        }
+    }
+
+    fn show_shear_result(
+        folder: &std::path::Path,
+        result: Result<JournalOutcome, String>,
+        available_space: Option<(u64, u64)>,
+    ) {
+        let (level, title, text) = match result {
+            Ok(outcome) if outcome.cancelled => (
+                native_dialog::MessageLevel::Warning,
+                "Cancelled",
+                "Shearing was cancelled. The installation is incomplete until the shear is resumed or rolled back.".to_owned(),
+            ),
+            Ok(outcome) if !outcome.failures.is_empty() => (
+                native_dialog::MessageLevel::Error,
+                "Failure",
+                format!(
+                    "Shearing stopped before anything was deleted, these could not be moved aside:\n{}\n\nClose whatever uses them and resume the shear, or roll it back.",
+                    outcome.failures.join("\n")
+                ),
+            ),
+            Ok(_) => (
+                native_dialog::MessageLevel::Info,
+                "Success",
+                format!(
+                    "\"{}\" has been successfully sheared.\n\n{}",
+                    folder.display(),
+                    Self::describe_available_space(available_space)
+                ),
+            ),
+            Err(reason) => (
+                native_dialog::MessageLevel::Error,
+                "Failure",
+                format!("Shearing failed.\n\n{reason}"),
+            ),
+        };
+
+        native_dialog::DialogBuilder::message()
+            .set_level(level)
+            .set_title(title)
+            .set_text(&text)
+            .alert()
+            .show()
+            .expect("Failed to show dialog");
+    }
+
+    fn finish_job(&mut self, result: ShearsJobResult) {
+        match result {
+            ShearsJobResult::Analysis {
+                folder,
+                features_availability,
+                game_settings,
+                shear_state,
+            } => {
+                // the folder changed while this analysis was running
+                if self.folder_state.siege_path.as_ref() != Some(&folder) {
+                    return;
+                }
+
+                self.folder_state.shear_state = shear_state;
+                self.apply_analysis(features_availability, game_settings);
+            }
+            ShearsJobResult::Shear {
+                folder,
+                result,
+                available_space,
+            } => {
+                self.refresh_install_history(&folder);
+
+                if !Journal::exists(&folder) {
+                    self.persistent_settings_storage
+                        .remove_pending_journal(&folder);
+                }
+
+                Self::show_shear_result(&folder, result, available_space);
+                self.set_folder(&folder);
+            }
+            ShearsJobResult::Batch { results } => self.finish_batch_job(results),
+            ShearsJobResult::Survey { survey } => self.finish_survey_job(survey),
+            ShearsJobResult::Compare { comparison } => self.finish_compare_job(comparison),
+            ShearsJobResult::InspectForges { forges } => self.finish_forge_inspection(forges),
+        }
+    }
+
+    pub(super) fn poll_job(&mut self) {
+        if let Some(result) = self.job_state.take_finished() {
+            self.finish_job(result);
+        }
+    }
+
+    pub(super) fn render_job_progress_page(&mut self, ctx: &egui::Context) {
+        if !self.job_state.is_running(ShearsJobKind::Shear) {
+            // nothing running, e.g. the job thread panicked
+            self.ui_state.replace_page(ShearsPage::FolderSelected);
+            return;
+        }
+
+        egui::CentralPanel::default()
+            .frame(egui::Frame::central_panel(&ctx.style()))
+            .show(ctx, |ui| {
+                let progress = self.job_state.progress.clone();
+
+                ui.heading("Shearing installation...");
+                let stage = progress.stage();
+                if !stage.is_empty() {
+                    ui.label(stage);
+                } else if let Some(siege_path) = &self.folder_state.siege_path {
+                    ui.label(egui::RichText::new(siege_path.display().to_string()).monospace());
+                }
+
+                ui.label(format!(
+                    "Time elapsed: {}",
+                    self.job_state.ongoing_timer_elapsed()
+                ));
+
+                if progress.total_bytes() > 0 {
+                    ui.add(
+                        egui::ProgressBar::new(
+                            progress.bytes() as f32 / progress.total_bytes() as f32,
+                        )
+                        .show_percentage(),
+                    );
+                }
+                ui.label(format!("Files removed: {}", progress.files()));
+                ui.label(format!(
+                    "Space freed: {}",
+                    format_reclaimable(progress.bytes())
+                ));
+                ui.label(egui::RichText::new(progress.current()).small().monospace());
+
+                // the shear stops between files and reports back as cancelled
+                let cancel_text = if progress.is_cancelled() {
+                    "Cancelling..."
+                } else {
+                    "Cancel"
+                };
+                if ui
+                    .add_enabled(!progress.is_cancelled(), egui::Button::new(cancel_text))
+                    .clicked()
+                {
+                    log::info!("Clicked on `Cancel` button");
+                    progress.cancel();
+                }
+
+                ctx.request_repaint();
+            });
+    }
+}
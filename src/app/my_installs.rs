@@ -0,0 +1,227 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    app::ShearsApp,
+    categories::{ShearSelection, find_category},
+    diskusage::format_reclaimable,
+    games::detect_game,
+    helpers::format_timestamp,
+    ledger::ShearLedger,
+    types::ShearsPage,
+};
+
+impl ShearsApp {
+    pub(super) fn remember_found_installs(&mut self, folders: &[PathBuf]) {
+        for folder in folders {
+            let game = detect_game(&self.game_definitions, folder);
+            self.install_registry.add_found(folder, &game);
+        }
+        self.install_registry.save_to_file();
+    }
+
+    // stores the size from the analysis that just finished and restores the last used selection
+    pub(super) fn apply_remembered_install(&mut self) {
+        let Some(siege_path) = &self.folder_state.siege_path else {
+            return;
+        };
+        let Some(install) = self.install_registry.get_mut(siege_path) else {
+            return;
+        };
+
+        install.size = Some(
+            self.folder_state
+                .features_availability
+                .install_size
+                .apparent,
+        );
+
+        let mut removed = install.removed.clone();
+        if !self
+            .persistent_settings_storage
+            .enable_experimental_features
+        {
+            removed.strip_experimental();
+        }
+        let advanced_selection = self.persistent_settings_storage.enable_advanced_selection;
+        self.ui_state.checkboxes = self
+            .folder_state
+            .features_availability
+            .categories
+            .iter()
+            .map(|category| removed.keep_for(category, advanced_selection))
+            .collect();
+
+        self.install_registry.save_to_file();
+    }
+
+    pub(super) fn remember_selection(&mut self) {
+        let Some(siege_path) = &self.folder_state.siege_path else {
+            return;
+        };
+        let Some(install) = self.install_registry.get_mut(siege_path) else {
+            return;
+        };
+
+        install.removed = ShearSelection::default();
+        for (category, keep) in self
+            .folder_state
+            .features_availability
+            .categories
+            .iter()
+            .zip(&self.ui_state.checkboxes)
+        {
+            let removed_keys: BTreeSet<String> = category
+                .items
+                .iter()
+                .zip(keep)
+                .filter(|&(item, &kept)| !kept && item.is_available())
+                .map(|(item, _)| item.key.clone())
+                .collect();
+            if !removed_keys.is_empty() {
+                install
+                    .removed
+                    .removed
+                    .insert(category.category.id().to_owned(), removed_keys);
+            }
+        }
+
+        self.install_registry.save_to_file();
+    }
+
+    // keeps the history shown on My installs in line with the ledger after a shear
+    pub(super) fn refresh_install_history(&mut self, folder: &Path) {
+        if self.install_registry.get(folder).is_some() {
+            self.install_histories
+                .insert(folder.to_path_buf(), ShearLedger::load(folder));
+        }
+    }
+
+    pub fn show_my_installs_page(&mut self) {
+        self.install_histories = self
+            .install_registry
+            .sorted()
+            .into_iter()
+            .map(|install| (install.path.clone(), ShearLedger::load(&install.path)))
+            .collect();
+        self.ui_state.change_page(ShearsPage::MyInstalls);
+    }
+
+    pub(super) fn render_my_installs_page(&mut self, ctx: &egui::Context) {
+        egui::CentralPanel::default()
+            .frame(egui::Frame::central_panel(&ctx.style()))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Back").clicked() {
+                        self.ui_state.go_back();
+                    }
+                });
+
+                ui.heading("My installs");
+
+                let paths: Vec<PathBuf> = self
+                    .install_registry
+                    .sorted()
+                    .into_iter()
+                    .map(|install| install.path.clone())
+                    .collect();
+                if paths.is_empty() {
+                    ui.label("Installs you open or find with a drive scan show up here.");
+                    return;
+                }
+
+                let mut open: Option<PathBuf> = None;
+                let mut forget: Option<PathBuf> = None;
+                let mut label_changed = false;
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for path in &paths {
+                        let Some(install) = self.install_registry.get_mut(path) else {
+                            continue;
+                        };
+                        let exists = path.is_dir();
+
+                        ui.group(|ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(install.display_name()).strong());
+                                if let Some(season) = &install.season {
+                                    ui.label(season);
+                                }
+                                if let Some(size) = install.size {
+                                    ui.label(humansize::format_size(size, humansize::WINDOWS));
+                                }
+                            });
+                            ui.label(egui::RichText::new(path.display().to_string()).monospace());
+
+                            if !exists {
+                                ui.label(
+                                    egui::RichText::new("Folder not found")
+                                        .color(egui::Color32::LIGHT_RED),
+                                );
+                            }
+
+                            ui.horizontal(|ui| {
+                                ui.label("Label:");
+                                label_changed |=
+                                    ui.text_edit_singleline(&mut install.label).lost_focus();
+                            });
+
+                            if let Some(last_opened) = install.last_opened {
+                                ui.label(format!("Last opened: {}", format_timestamp(last_opened)));
+                            }
+
+                            let history = self
+                                .install_histories
+                                .get(path)
+                                .map(|ledger| ledger.shears.as_slice())
+                                .unwrap_or_default();
+                            if !history.is_empty() {
+                                ui.collapsing(format!("Sheared {} times", history.len()), |ui| {
+                                    for entry in history.iter().rev() {
+                                        let categories: Vec<&str> = entry
+                                            .categories
+                                            .iter()
+                                            .map(|id| {
+                                                find_category(id)
+                                                    .map_or(id.as_str(), |category| category.name())
+                                            })
+                                            .collect();
+                                        ui.label(format!(
+                                            "{}: {} removed, {} freed",
+                                            format_timestamp(entry.date),
+                                            categories.join(", "),
+                                            format_reclaimable(entry.freed)
+                                        ));
+                                    }
+                                });
+                            }
+
+                            ui.horizontal(|ui| {
+                                if ui.add_enabled(exists, egui::Button::new("Open")).clicked() {
+                                    open = Some(path.clone());
+                                }
+                                if ui.button("Forget").clicked() {
+                                    forget = Some(path.clone());
+                                }
+                            });
+                        });
+                    }
+                });
+
+                if label_changed {
+                    self.install_registry.save_to_file();
+                }
+
+                if let Some(path) = forget {
+                    self.install_registry.forget(&path);
+                    self.install_registry.save_to_file();
+                }
+
+                if let Some(path) = open {
+                    self.set_folder(&path);
+                }
+            });
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{app::ShearsApp, categories::ShearSelection, profiles::ShearProfile};
+
+fn show_profile_error(text: &str) {
+    native_dialog::DialogBuilder::message()
+        .set_level(native_dialog::MessageLevel::Error)
+        .set_title("Error")
+        .set_text(text)
+        .alert()
+        .show()
+        .expect("Failed to show dialog");
+}
+
+impl ShearsApp {
+    fn reload_profiles(&mut self) {
+        self.profiles = ShearProfile::load_all();
+    }
+
+    // the part of a profile the user can see, categories hidden behind experimental features are left alone
+    pub(super) fn profile_selection(&self, profile: &ShearProfile) -> ShearSelection {
+        let mut selection = profile.selection.clone();
+        if self
+            .persistent_settings_storage
+            .enable_experimental_features
+        {
+            return selection;
+        }
+
+        let hidden = selection.strip_experimental();
+        if !hidden.is_empty() {
+            native_dialog::DialogBuilder::message()
+                .set_level(native_dialog::MessageLevel::Warning)
+                .set_title("Experimental features are disabled")
+                .set_text(format!(
+                    "The profile \"{}\" also removes {}. That part was left out, enable experimental features to apply it.",
+                    profile.name,
+                    hidden.join(", ").to_lowercase()
+                ))
+                .alert()
+                .show()
+                .expect("Failed to show dialog");
+        }
+        selection
+    }
+
+    // a profile whose file is already taken is only saved once the user agrees to replace it
+    fn confirm_profile_save(&self, profile: &ShearProfile) -> bool {
+        let Some(existing) = profile.find_existing(&self.profiles) else {
+            return true;
+        };
+
+        if existing.name != profile.name {
+            show_profile_error(&format!(
+                "The name \"{}\" is too close to the existing profile \"{}\", choose another one.",
+                profile.name, existing.name
+            ));
+            return false;
+        }
+
+        native_dialog::DialogBuilder::message()
+            .set_level(native_dialog::MessageLevel::Warning)
+            .set_title("Replace profile")
+            .set_text(format!(
+                "A profile named \"{}\" already exists. Replace it?",
+                profile.name
+            ))
+            .confirm()
+            .show()
+            .expect("Failed to show dialog")
+    }
+
+    fn apply_profile(&mut self, profile: &ShearProfile) {
+        log::info!("Applying profile `{}`", profile.name);
+        self.ui_state.applied_profile = Some(profile.name.clone());
+
+        let selection = self.profile_selection(profile);
+        let advanced_selection = self.persistent_settings_storage.enable_advanced_selection;
+        self.ui_state.checkboxes = self
+            .folder_state
+            .features_availability
+            .categories
+            .iter()
+            .map(|category| selection.keep_for(category, advanced_selection))
+            .collect();
+        self.on_selection_changed();
+    }
+
+    fn save_current_selection_as_profile(&mut self) {
+        let name = self.ui_state.profile_name.trim().to_owned();
+        if name.is_empty() {
+            return;
+        }
+
+        let profile = ShearProfile::from_selection(
+            &name,
+            &self.folder_state.features_availability.categories,
+            &self.ui_state.checkboxes,
+        );
+        if !self.confirm_profile_save(&profile) {
+            return;
+        }
+        match profile.save() {
+            Ok(()) => self.ui_state.profile_name.clear(),
+            Err(e) => show_profile_error(&format!("Failed to save the profile: {e}")),
+        }
+        self.reload_profiles();
+    }
+
+    fn export_profile(profile: &ShearProfile) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_file_name(profile.file_name())
+            .add_filter("Shears profile", &["toml"])
+            .save_file()
+        else {
+            return;
+        };
+
+        if let Err(e) = profile.write(&path) {
+            show_profile_error(&format!("Failed to export the profile: {e}"));
+        }
+    }
+
+    fn import_profile(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Shears profile", &["toml"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        let profile = match ShearProfile::read(&path) {
+            Ok(profile) => profile,
+            Err(e) => {
+                show_profile_error(&format!("Failed to import the profile: {e}"));
+                return;
+            }
+        };
+        if !self.confirm_profile_save(&profile) {
+            return;
+        }
+
+        match profile.save() {
+            Ok(()) => log::info!("Imported profile from {}", path.display()),
+            Err(e) => show_profile_error(&format!("Failed to import the profile: {e}")),
+        }
+        self.reload_profiles();
+    }
+
+    // a row of buttons, one per profile, returns the one that was clicked
+    pub(super) fn render_profile_buttons(&self, ui: &mut egui::Ui) -> Option<ShearProfile> {
+        let mut clicked = None;
+        ui.horizontal_wrapped(|ui| {
+            for profile in &self.profiles {
+                if ui.button(&profile.name).clicked() {
+                    clicked = Some(profile.clone());
+                }
+            }
+        });
+        clicked
+    }
+
+    pub(super) fn render_folder_selected_page_profiles(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.heading("Profiles");
+                if ui.button("Import...").clicked() {
+                    self.import_profile();
+                }
+            });
+
+            if self.profiles.is_empty() {
+                ui.label(
+                    "Save a selection as a profile to apply it to other installs with one click.",
+                );
+            } else {
+                ui.label("Apply a profile");
+                if let Some(profile) = self.render_profile_buttons(ui) {
+                    self.apply_profile(&profile);
+                }
+
+                let mut deleted = false;
+                ui.collapsing("Manage profiles", |ui| {
+                    for profile in &self.profiles {
+                        ui.horizontal(|ui| {
+                            ui.label(&profile.name);
+                            if ui.button("Export...").clicked() {
+                                Self::export_profile(profile);
+                            }
+                            if ui.button("Delete").clicked() {
+                                if let Err(e) = profile.delete() {
+                                    show_profile_error(&format!(
+                                        "Failed to delete the profile: {e}"
+                                    ));
+                                }
+                                deleted = true;
+                            }
+                        });
+                    }
+                });
+                if deleted {
+                    self.reload_profiles();
+                }
+            }
+
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.ui_state.profile_name);
+                if ui
+                    .add_enabled(
+                        !self.ui_state.profile_name.trim().is_empty(),
+                        egui::Button::new("Save selection as profile"),
+                    )
+                    .clicked()
+                {
+                    self.save_current_selection_as_profile();
+                }
+            });
+        });
+    }
+}
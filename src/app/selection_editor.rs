@@ -0,0 +1,81 @@
+use crate::categories::{ShearCategory, ShearSelection};
+
+// edits a selection that isn't tied to an install, using the items every install of the game can have.
+// returns true when the selection changed
+pub(super) fn render_selection_editor(
+    ui: &mut egui::Ui,
+    categories: &[&'static dyn ShearCategory],
+    selection: &mut ShearSelection,
+    show_experimental: bool,
+    advanced_selection: bool,
+) -> bool {
+    let mut changed = false;
+
+    for &category in categories {
+        if category.is_experimental() && !show_experimental {
+            continue;
+        }
+
+        ui.separator();
+        ui.label(egui::RichText::new(category.name()).strong());
+
+        let known_items = category.known_items();
+        if known_items.is_empty() {
+            let mut keep_all = !selection.remove_all.contains(category.id());
+            if ui
+                .checkbox(&mut keep_all, format!("Keep all {}", category.name()))
+                .changed()
+            {
+                if keep_all {
+                    selection.remove_all.remove(category.id());
+                } else {
+                    selection.remove_all.insert(category.id().to_owned());
+                }
+                changed = true;
+            }
+            continue;
+        }
+
+        let uses_advanced_selection = advanced_selection && category.supports_advanced_selection();
+        let mut keep: Vec<bool> = known_items
+            .iter()
+            .map(|item| !selection.is_removed(category, &item.key))
+            .collect();
+
+        let mut category_changed = false;
+        for (item_index, item) in known_items.iter().enumerate() {
+            ui.add_enabled_ui(
+                uses_advanced_selection || !category.is_locked(item_index),
+                |ui| {
+                    if ui
+                        .checkbox(
+                            keep.get_mut(item_index).expect("Out of bounds error"),
+                            &item.label,
+                        )
+                        .clicked()
+                    {
+                        if !uses_advanced_selection {
+                            category.validate_selection(&mut keep, item_index);
+                        }
+                        category_changed = true;
+                    }
+                },
+            );
+        }
+
+        if category_changed {
+            selection.removed.insert(
+                category.id().to_owned(),
+                known_items
+                    .iter()
+                    .zip(&keep)
+                    .filter(|&(_, &kept)| !kept)
+                    .map(|(item, _)| item.key.clone())
+                    .collect(),
+            );
+            changed = true;
+        }
+    }
+
+    changed
+}
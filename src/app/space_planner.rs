@@ -0,0 +1,335 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    app::ShearsApp,
+    diskusage::format_reclaimable,
+    planner::{CutId, PlannedCut, SpacePlan, SpaceSurvey, plan_space, survey_installs},
+    types::{ShearsJobKind, ShearsJobResult, ShearsPage},
+};
+
+const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+fn gb_to_bytes(gb: f64) -> u64 {
+    (gb.max(0.0) * BYTES_PER_GB) as u64
+}
+
+impl ShearsApp {
+    pub fn show_space_planner_page(&mut self) {
+        self.scan_state.update_disks();
+        self.ui_state.change_page(ShearsPage::SpacePlanner);
+    }
+
+    fn start_survey_job(&mut self) {
+        let folders: Vec<PathBuf> = self
+            .install_registry
+            .sorted()
+            .into_iter()
+            .map(|install| install.path.clone())
+            .filter(|path| path.is_dir())
+            .collect();
+        let definitions = self.game_definitions.clone();
+        let size_cache = self.size_cache.clone();
+
+        self.planner_state.survey = None;
+        self.planner_state.excluded.clear();
+        self.job_state
+            .start_job(ShearsJobKind::Survey, move |progress| {
+                let survey = survey_installs(&folders, &definitions, &size_cache, progress);
+                if !progress.is_cancelled() {
+                    size_cache.save_to_file();
+                }
+                ShearsJobResult::Survey { survey }
+            });
+    }
+
+    pub(super) fn finish_survey_job(&mut self, survey: SpaceSurvey) {
+        self.planner_state.survey = Some(survey);
+    }
+
+    fn available_space_on(&self, mount_point: &Path) -> Option<u64> {
+        self.scan_state
+            .disks
+            .iter()
+            .find(|disk| disk.mount_point() == mount_point)
+            .map(sysinfo::Disk::available_space)
+    }
+
+    // the drive to plan for, if any, and how many bytes have to be freed
+    fn planner_target(&self) -> (Option<PathBuf>, u64) {
+        if !self.planner_state.keep_free {
+            return (None, gb_to_bytes(self.planner_state.amount_gb));
+        }
+
+        let Some(mount_point) = &self.planner_state.mount_point else {
+            return (None, 0);
+        };
+        let available = self.available_space_on(mount_point).unwrap_or(0);
+        let minimum = gb_to_bytes(self.planner_state.minimum_free_gb);
+
+        (Some(mount_point.clone()), minimum.saturating_sub(available))
+    }
+
+    fn render_space_planner_target(&mut self, ui: &mut egui::Ui) {
+        ui.radio_value(
+            &mut self.planner_state.keep_free,
+            false,
+            "Free a fixed amount of space",
+        );
+        if !self.planner_state.keep_free {
+            ui.horizontal(|ui| {
+                ui.label("Free at least");
+                ui.add(
+                    egui::DragValue::new(&mut self.planner_state.amount_gb)
+                        .range(0.0..=100_000.0)
+                        .suffix(" GB"),
+                );
+                ui.label("across all installs");
+            });
+        }
+
+        ui.radio_value(
+            &mut self.planner_state.keep_free,
+            true,
+            "Keep a minimum of free space on a drive",
+        );
+        if !self.planner_state.keep_free {
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            let selected_text = self
+                .planner_state
+                .mount_point
+                .as_ref()
+                .map_or_else(|| "Select a drive".to_owned(), |m| m.display().to_string());
+
+            egui::ComboBox::from_id_salt("space_planner_drive")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for disk in &self.scan_state.disks {
+                        let mount_point = disk.mount_point().to_path_buf();
+                        let text = format!(
+                            "{} ({} free)",
+                            mount_point.display(),
+                            humansize::format_size(disk.available_space(), humansize::WINDOWS)
+                        );
+                        ui.selectable_value(
+                            &mut self.planner_state.mount_point,
+                            Some(mount_point),
+                            text,
+                        );
+                    }
+                });
+
+            ui.label("should have at least");
+            ui.add(
+                egui::DragValue::new(&mut self.planner_state.minimum_free_gb)
+                    .range(0.0..=100_000.0)
+                    .suffix(" GB"),
+            );
+            ui.label("free");
+        });
+
+        if let Some(mount_point) = &self.planner_state.mount_point
+            && let Some(available) = self.available_space_on(mount_point)
+        {
+            ui.label(format!(
+                "Currently free: {}. Only installs on this drive are considered.",
+                humansize::format_size(available, humansize::WINDOWS)
+            ));
+        }
+
+        let unknown_drive = self
+            .planner_state
+            .survey
+            .iter()
+            .flat_map(|survey| &survey.installs)
+            .filter(|install| install.mount_point.is_none())
+            .count();
+        if unknown_drive > 0 {
+            ui.label(
+                egui::RichText::new(format!(
+                    "The drive of {unknown_drive} installs couldn't be found, they aren't considered."
+                ))
+                .color(ui.visuals().warn_fg_color),
+            );
+        }
+    }
+
+    fn render_space_planner_cut(
+        ui: &mut egui::Ui,
+        cut: &PlannedCut,
+        included: bool,
+        toggled: &mut Option<CutId>,
+    ) {
+        let mut checked = included;
+        let text = format!(
+            "{}: {} ({})",
+            cut.category.name(),
+            cut.label,
+            format_reclaimable(cut.size.reclaimable)
+        );
+        if ui.checkbox(&mut checked, text).changed() {
+            *toggled = Some(cut.id.clone());
+        }
+    }
+
+    fn render_space_planner_proposal(&self, ui: &mut egui::Ui, plan: &SpacePlan) -> Option<CutId> {
+        let survey = self.planner_state.survey.as_ref()?;
+        let mut toggled = None;
+
+        egui::ScrollArea::vertical()
+            .max_height(ui.available_height() - 40.0)
+            .show(ui, |ui| {
+                for install in &survey.installs {
+                    let folder = &install.folder;
+                    let has_cuts = plan.cuts_for(folder).next().is_some()
+                        || plan.excluded.iter().any(|cut| cut.id.folder == *folder);
+                    if !has_cuts {
+                        continue;
+                    }
+
+                    ui.group(|ui| {
+                        let name = self
+                            .install_registry
+                            .get(folder)
+                            .map_or_else(|| folder.display().to_string(), |i| i.display_name());
+                        ui.label(egui::RichText::new(name).strong());
+                        ui.label(egui::RichText::new(folder.display().to_string()).small());
+
+                        for cut in plan.cuts_for(folder) {
+                            Self::render_space_planner_cut(ui, cut, true, &mut toggled);
+                        }
+                        for cut in plan.excluded.iter().filter(|cut| cut.id.folder == *folder) {
+                            Self::render_space_planner_cut(ui, cut, false, &mut toggled);
+                        }
+                    });
+                }
+
+                if !survey.skipped.is_empty() {
+                    ui.collapsing(
+                        format!("{} installs were left out", survey.skipped.len()),
+                        |ui| {
+                            for (folder, reason) in &survey.skipped {
+                                ui.label(format!("{}: {reason}", folder.display()));
+                            }
+                        },
+                    );
+                }
+            });
+
+        toggled
+    }
+
+    fn apply_space_plan(&mut self, plan: &SpacePlan) {
+        if self.confirm_and_start_batch_job(plan.selections(), None, ShearsPage::SpacePlanner) {
+            // the sizes are stale once anything was sheared
+            self.planner_state.survey = None;
+            self.planner_state.excluded.clear();
+        }
+    }
+
+    pub(super) fn render_space_planner_page(&mut self, ctx: &egui::Context) {
+        egui::CentralPanel::default()
+            .frame(egui::Frame::central_panel(&ctx.style()))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Back").clicked() {
+                        if self.job_state.is_running(ShearsJobKind::Survey) {
+                            self.job_state.abandon();
+                        }
+                        self.ui_state.go_back();
+                    }
+                });
+
+                ui.heading("Space planner");
+                ui.label("Proposes the least destructive cuts across your installs that free the space you need: leftovers first, then Ultra textures, videos, Very High textures and so on. Renderers, languages and events are left alone.");
+
+                self.render_space_planner_target(ui);
+                ui.separator();
+
+                if self.job_state.is_running(ShearsJobKind::Survey) {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(self.job_state.progress.stage());
+                    });
+                    ui.label(
+                        egui::RichText::new(self.job_state.progress.current())
+                            .small()
+                            .monospace(),
+                    );
+                    ctx.request_repaint();
+                    return;
+                }
+
+                let analyze_text = if self.planner_state.survey.is_some() {
+                    "Analyze installs again"
+                } else {
+                    "Analyze my installs"
+                };
+                if ui
+                    .add_enabled(
+                        !self.job_state.is_running(ShearsJobKind::Shear),
+                        egui::Button::new(analyze_text),
+                    )
+                    .on_hover_text("Measures every install listed in My installs")
+                    .clicked()
+                {
+                    log::info!("Clicked on `{analyze_text}` button");
+                    self.start_survey_job();
+                    return;
+                }
+
+                let Some(survey) = &self.planner_state.survey else {
+                    return;
+                };
+
+                let (mount_point, target) = self.planner_target();
+                if self.planner_state.keep_free && mount_point.is_none() {
+                    ui.label("Select a drive to plan for.");
+                    return;
+                }
+                let plan = plan_space(
+                    survey,
+                    mount_point.as_deref(),
+                    target,
+                    &self.planner_state.excluded,
+                );
+
+                if target == 0 {
+                    ui.label("Nothing has to be freed to reach this target.");
+                    return;
+                }
+
+                ui.label(format!(
+                    "Target: {}, proposed cuts reclaim {} in {} installs",
+                    humansize::format_size(target, humansize::WINDOWS),
+                    format_reclaimable(plan.freed),
+                    plan.selections().len()
+                ));
+                if !plan.reaches_target {
+                    ui.label(
+                        egui::RichText::new(
+                            "Even with every allowed cut the target can't be reached.",
+                        )
+                        .color(ui.visuals().warn_fg_color),
+                    );
+                }
+
+                if let Some(toggled) = self.render_space_planner_proposal(ui, &plan) {
+                    if !self.planner_state.excluded.remove(&toggled) {
+                        self.planner_state.excluded.insert(toggled);
+                    }
+                    return;
+                }
+
+                if ui
+                    .add_enabled(!plan.cuts.is_empty(), egui::Button::new("Apply as batch"))
+                    .clicked()
+                {
+                    log::info!("Clicked on `Apply as batch` button");
+                    self.apply_space_plan(&plan);
+                }
+            });
+    }
+}
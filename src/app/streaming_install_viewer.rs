@@ -0,0 +1,98 @@
+use crate::{
+    app::ShearsApp,
+    streaminginstall::StreamingInstall,
+    types::{ShearsPage, ShearsStreamingInstallState},
+};
+
+impl ShearsApp {
+    pub fn show_streaming_install_page(&mut self) {
+        let Some(siege_path) = &self.folder_state.siege_path else {
+            return;
+        };
+
+        let streaming_install = StreamingInstall::load(siege_path);
+        self.streaming_install_state = ShearsStreamingInstallState {
+            shears_version: streaming_install
+                .as_ref()
+                .ok()
+                .and_then(StreamingInstall::shears_version),
+            chunks: streaming_install
+                .map(|streaming_install| streaming_install.chunk_status(siege_path))
+                .map_err(|e| e.to_string()),
+        };
+        self.ui_state
+            .change_page(ShearsPage::StreamingInstallViewer);
+    }
+
+    pub(super) fn render_streaming_install_page(&mut self, ctx: &egui::Context) {
+        egui::CentralPanel::default()
+            .frame(egui::Frame::central_panel(&ctx.style()))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Back").clicked() {
+                        self.ui_state.go_back();
+                    }
+                });
+
+                ui.heading("Chunk mappings");
+
+                if let Some(version) = &self.streaming_install_state.shears_version {
+                    ui.label(format!(
+                        "Last rewritten by Shears {version}, verifying the game files restores the original"
+                    ));
+                }
+
+                let chunks = match &self.streaming_install_state.chunks {
+                    Ok(chunks) => chunks,
+                    Err(e) => {
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "Unable to read streaminginstall.ini: {e}"
+                            ))
+                            .color(egui::Color32::LIGHT_RED),
+                        );
+                        return;
+                    }
+                };
+
+                let missing_count = chunks.iter().filter(|chunk| chunk.is_missing()).count();
+                ui.label(format!(
+                    "{} chunks mapped, {missing_count} missing",
+                    chunks.len()
+                ));
+
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for chunk in chunks {
+                            let title = if chunk.is_missing() {
+                                egui::RichText::new(format!("Chunk {} (missing)", chunk.chunk))
+                                    .color(egui::Color32::LIGHT_RED)
+                            } else {
+                                egui::RichText::new(format!("Chunk {}", chunk.chunk))
+                            };
+
+                            ui.collapsing(title, |ui| {
+                                for (file, exists) in &chunk.files {
+                                    if *exists {
+                                        ui.monospace(file);
+                                    } else {
+                                        ui.label(
+                                            egui::RichText::new(format!("{file} (missing)"))
+                                                .monospace()
+                                                .color(egui::Color32::LIGHT_RED),
+                                        );
+                                    }
+                                }
+
+                                if !chunk.missions.is_empty() {
+                                    ui.label(format!("Missions: {}", chunk.missions.join(", ")));
+                                }
+                            });
+                        }
+                    });
+            });
+    }
+}
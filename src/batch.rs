@@ -0,0 +1,165 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    categories::{ShearPlan, ShearSelection},
+    games::{GameDefinition, detect_game},
+    helpers::get_shearing_features_availability,
+    journal::{Journal, JournalOutcome, shear},
+    progress::JobProgress,
+    sizecache::SizeCache,
+};
+
+#[derive(Debug)]
+pub enum BatchStatus {
+    Sheared(JournalOutcome),
+    NothingToRemove,
+    Skipped, // the batch was cancelled before this install was sheared
+    Failed(String),
+}
+
+#[derive(Debug)]
+pub struct BatchInstallResult {
+    pub folder: PathBuf,
+    pub status: BatchStatus,
+    pub removed_items: Vec<String>,
+    pub freed: u64,
+}
+
+impl BatchInstallResult {
+    pub fn is_success(&self) -> bool {
+        match &self.status {
+            BatchStatus::Sheared(outcome) => !outcome.cancelled && outcome.failures.is_empty(),
+            BatchStatus::NothingToRemove => true,
+            BatchStatus::Skipped | BatchStatus::Failed(_) => false,
+        }
+    }
+}
+
+fn shear_install(
+    folder: &Path,
+    definitions: &[GameDefinition],
+    selection: &ShearSelection,
+    advanced_selection: bool,
+    profile: Option<&str>,
+    cache: &SizeCache,
+    progress: &JobProgress,
+) -> BatchInstallResult {
+    let mut result = BatchInstallResult {
+        folder: folder.to_path_buf(),
+        status: BatchStatus::NothingToRemove,
+        removed_items: Vec::new(),
+        freed: 0,
+    };
+
+    // an interrupted shear has to be dealt with by the user, a batch can't decide for them
+    if Journal::exists(folder) {
+        result.status = BatchStatus::Failed(
+            "This install has an interrupted shear, resume or roll it back first.".to_owned(),
+        );
+        return result;
+    }
+
+    let game = detect_game(definitions, folder);
+    let features_availability =
+        get_shearing_features_availability(folder, &game.shear_categories(), cache, progress);
+    if progress.is_cancelled() {
+        result.status = BatchStatus::Skipped;
+        return result;
+    }
+
+    if !features_availability.has_forge_files {
+        result.status = BatchStatus::Failed("Folder does not contain FORGE files.".to_owned());
+        return result;
+    }
+    if !features_availability.damaged_forges.is_empty() {
+        result.status = BatchStatus::Failed(format!(
+            "{} forge files are invalid or truncated, verify the game files first.",
+            features_availability.damaged_forges.len()
+        ));
+        return result;
+    }
+
+    let checkboxes: Vec<Vec<bool>> = features_availability
+        .categories
+        .iter()
+        .map(|category| selection.keep_for(category, advanced_selection))
+        .collect();
+
+    result.removed_items = features_availability
+        .categories
+        .iter()
+        .zip(&checkboxes)
+        .flat_map(|(category, keep)| {
+            category
+                .items
+                .iter()
+                .zip(keep)
+                .filter(|&(item, &kept)| !kept && item.is_available())
+                .map(|(item, _)| format!("{}: {}", category.category.name(), item.label))
+        })
+        .collect();
+    if result.removed_items.is_empty() {
+        return result;
+    }
+
+    let plan = ShearPlan::new(&features_availability.categories, &checkboxes);
+    let bytes_before = progress.bytes();
+    result.status = match shear(folder, &game, &plan, profile.map(str::to_owned), progress) {
+        Ok(outcome) => BatchStatus::Sheared(outcome),
+        Err(reason) => BatchStatus::Failed(reason),
+    };
+    result.freed = progress.bytes() - bytes_before;
+
+    result
+}
+
+// analyzes, plans and shears every install in turn, each with its own selection
+pub fn run_batch(
+    jobs: &[(PathBuf, ShearSelection)],
+    advanced_selection: bool,
+    profile: Option<&str>,
+    definitions: &[GameDefinition],
+    cache: &SizeCache,
+    progress: &JobProgress,
+) -> Vec<BatchInstallResult> {
+    jobs.iter()
+        .enumerate()
+        .map(|(index, (folder, selection))| {
+            if progress.is_cancelled() {
+                return BatchInstallResult {
+                    folder: folder.clone(),
+                    status: BatchStatus::Skipped,
+                    removed_items: Vec::new(),
+                    freed: 0,
+                };
+            }
+
+            progress.set_stage(format!(
+                "Install {} of {}: {}",
+                index + 1,
+                jobs.len(),
+                folder.display()
+            ));
+            let result = shear_install(
+                folder,
+                definitions,
+                selection,
+                advanced_selection,
+                profile,
+                cache,
+                progress,
+            );
+
+            if let BatchStatus::Failed(reason) = &result.status {
+                log::warn!("Batch shear of {} failed: {reason}", folder.display());
+            } else {
+                log::info!(
+                    "Batch shear of {} done, {} bytes freed",
+                    folder.display(),
+                    result.freed
+                );
+            }
+            result
+        })
+        .collect()
+}
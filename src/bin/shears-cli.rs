@@ -0,0 +1,10 @@
+#![warn(clippy::all, rust_2018_idioms)]
+
+// the command line lives in its own console binary, release builds of the GUI have no console on
+// Windows, so anything they print is lost
+fn main() {
+    env_logger::init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    std::process::exit(shears::run_cli(&args));
+}
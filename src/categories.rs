@@ -0,0 +1,521 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    diskusage::DiskUsage,
+    guard::{DeletionGuard, GuardError},
+    sizecache::SizeCache,
+};
+
+mod events;
+mod languages;
+mod leftovers;
+mod renderer;
+mod textures;
+mod videos;
+
+pub use events::EventsCategory;
+pub use languages::LanguagesCategory;
+pub use leftovers::LeftoversCategory;
+pub use renderer::RendererCategory;
+pub use textures::TexturesCategory;
+pub use videos::VideosCategory;
+
+// a single removable thing inside a category, e.g. the "High" texture tier or the videos folder
+#[derive(Clone, Debug, Default)]
+pub struct CategoryItem {
+    pub key: String,
+    pub label: String,
+    pub paths: Vec<PathBuf>,
+    pub size: DiskUsage,
+    pub unreadable: Vec<(PathBuf, String)>, // entries that couldn't be measured, `size` leaves them out
+}
+
+impl CategoryItem {
+    pub fn new(key: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            label: label.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.size.apparent > 0 || self.is_partially_measured()
+    }
+
+    pub fn is_partially_measured(&self) -> bool {
+        !self.unreadable.is_empty()
+    }
+}
+
+pub trait ShearCategory: std::fmt::Debug + Sync {
+    fn id(&self) -> &'static str;
+
+    fn name(&self) -> &'static str;
+
+    fn is_experimental(&self) -> bool {
+        false
+    }
+
+    // items that exist no matter the install, used to pick a selection before anything is analyzed.
+    // categories whose items depend on the install return none and can only be removed as a whole
+    fn known_items(&self) -> Vec<CategoryItem> {
+        Vec::new()
+    }
+
+    // how readily the space planner cuts an item, lower goes first. items without a priority are
+    // left to the user, e.g. which renderer or language to keep depends on how the game is played
+    fn cut_priority(&self, _key: &str) -> Option<u32> {
+        None
+    }
+
+    // whether the removed files came from the game depot, so a DepotDownloader file list can bring
+    // them back. leftovers aren't part of any depot, renderers and languages aren't exported either
+    fn is_redownloadable(&self) -> bool {
+        false
+    }
+
+    // finds every item of this category inside the folder, sizes are filled in later by `size`
+    fn detect(&self, folder: &Path) -> Vec<CategoryItem>;
+
+    fn size(
+        &self,
+        item: &CategoryItem,
+        cache: &SizeCache,
+        unreadable: &mut Vec<(PathBuf, String)>,
+    ) -> DiskUsage {
+        item.paths
+            .iter()
+            .map(|path| cache.path_usage(path, unreadable))
+            .sum()
+    }
+
+    // locked items can never be removed by the user
+    fn is_locked(&self, _item_index: usize) -> bool {
+        false
+    }
+
+    // called after the user toggles `changed_index`, lets a category enforce rules between its items
+    fn validate_selection(&self, _keep: &mut [bool], _changed_index: usize) {}
+
+    // advanced selection lifts the locks and the selection rules, `check_plan` has the final say
+    fn supports_advanced_selection(&self) -> bool {
+        false
+    }
+
+    // refuses plans that would break the install, the error explains why to the user. runs on every
+    // checkbox click, so it only looks at `items`: the analysis while picking, detected anew right before shearing
+    fn check_plan(&self, _items: &[CategoryItem], _plan: &[PathBuf]) -> Result<(), String> {
+        Ok(())
+    }
+
+    // the part of the check that has to read the install, only runs right before shearing
+    fn check_files(
+        &self,
+        _folder: &Path,
+        _items: &[CategoryItem],
+        _plan: &[PathBuf],
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn plan(&self, items: &[CategoryItem], keep: &[bool]) -> Vec<PathBuf> {
+        items
+            .iter()
+            .zip(keep)
+            .filter(|&(item, &kept)| !kept && item.is_available())
+            .flat_map(|(item, _)| item.paths.iter().cloned())
+            .collect()
+    }
+
+    // removals are staged first and only deleted once the whole shear went through
+    fn apply(
+        &self,
+        guard: &DeletionGuard,
+        path: &Path,
+        staging_path: &Path,
+    ) -> Result<(), GuardError> {
+        guard.move_into(path, staging_path)
+    }
+}
+
+static REGISTRY: [&dyn ShearCategory; 6] = [
+    &TexturesCategory,
+    &VideosCategory,
+    &EventsCategory,
+    &RendererCategory,
+    &LanguagesCategory,
+    &LeftoversCategory,
+];
+
+// for categories where the user picks what to keep among alternatives, e.g. renderers or languages
+pub fn keep_at_least_one(keep: &mut [bool], changed_index: usize) {
+    if keep.iter().all(|&kept| !kept)
+        && let Some(changed) = keep.get_mut(changed_index)
+    {
+        *changed = true;
+    }
+}
+
+// the plan side of `keep_at_least_one`, a plan has to leave one of the installed items alone
+pub fn check_keeps_one(items: &[CategoryItem], plan: &[PathBuf], what: &str) -> Result<(), String> {
+    let mut installed = items
+        .iter()
+        .filter(|item| !item.paths.is_empty())
+        .peekable();
+    if installed.peek().is_none() {
+        return Ok(());
+    }
+
+    if installed.any(|item| item.paths.iter().any(|path| !plan.contains(path))) {
+        Ok(())
+    } else {
+        Err(format!("At least one {what} has to be kept."))
+    }
+}
+
+pub fn registry() -> &'static [&'static dyn ShearCategory] {
+    &REGISTRY
+}
+
+pub fn find_category(id: &str) -> Option<&'static dyn ShearCategory> {
+    registry()
+        .iter()
+        .find(|category| category.id() == id)
+        .copied()
+}
+
+#[derive(Clone, Debug)]
+pub struct CategoryAvailability {
+    pub category: &'static dyn ShearCategory,
+    pub items: Vec<CategoryItem>,
+}
+
+impl CategoryAvailability {
+    pub fn analyze(category: &'static dyn ShearCategory, folder: &Path, cache: &SizeCache) -> Self {
+        let mut items = category.detect(folder);
+        for item in &mut items {
+            let mut unreadable = Vec::new();
+            item.size = category.size(item, cache, &mut unreadable);
+            for (path, reason) in &unreadable {
+                log::warn!("Unable to measure {}: {reason}", path.display());
+            }
+            item.unreadable = unreadable;
+
+            log::info!(
+                "{}.{}: {} paths, {} bytes, {} reclaimable",
+                category.id(),
+                item.key,
+                item.paths.len(),
+                item.size.apparent,
+                item.size.reclaimable
+            );
+        }
+
+        Self { category, items }
+    }
+
+    // what the checkboxes should look like right after analysis: keep everything that exists
+    pub fn default_selection(&self) -> Vec<bool> {
+        self.items.iter().map(CategoryItem::is_available).collect()
+    }
+
+    fn uses_advanced_selection(&self, advanced_selection: bool) -> bool {
+        advanced_selection && self.category.supports_advanced_selection()
+    }
+
+    pub fn is_item_enabled(&self, item_index: usize, advanced_selection: bool) -> bool {
+        (self.uses_advanced_selection(advanced_selection) || !self.category.is_locked(item_index))
+            && self
+                .items
+                .get(item_index)
+                .is_some_and(CategoryItem::is_available)
+    }
+
+    pub fn validate_selection(
+        &self,
+        keep: &mut [bool],
+        changed_index: usize,
+        advanced_selection: bool,
+    ) {
+        if !self.uses_advanced_selection(advanced_selection) {
+            self.category.validate_selection(keep, changed_index);
+        }
+    }
+
+    pub fn is_partially_measured(&self) -> bool {
+        self.items.iter().any(CategoryItem::is_partially_measured)
+    }
+
+    pub fn total_size(&self) -> DiskUsage {
+        self.items.iter().map(|item| item.size).sum()
+    }
+
+    pub fn removed_size(&self, keep: &[bool]) -> DiskUsage {
+        self.items
+            .iter()
+            .zip(keep)
+            .filter(|&(item, &kept)| !kept && item.is_available())
+            .map(|(item, _)| item.size)
+            .sum()
+    }
+}
+
+// what to remove without being tied to one install, so the same choice can be applied to several.
+// categories without known items can only be removed as a whole through `remove_all`
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ShearSelection {
+    pub removed: BTreeMap<String, BTreeSet<String>>,
+    pub remove_all: BTreeSet<String>,
+}
+
+impl ShearSelection {
+    pub fn is_removed(&self, category: &dyn ShearCategory, key: &str) -> bool {
+        self.remove_all.contains(category.id())
+            || self
+                .removed
+                .get(category.id())
+                .is_some_and(|keys| keys.contains(key))
+    }
+
+    // drops what the selection removes from experimental categories, returns their names
+    pub fn strip_experimental(&mut self) -> Vec<&'static str> {
+        let mut stripped = Vec::new();
+        for category in registry()
+            .iter()
+            .filter(|category| category.is_experimental())
+        {
+            let removed_all = self.remove_all.remove(category.id());
+            let removed_some = self
+                .removed
+                .remove(category.id())
+                .is_some_and(|keys| !keys.is_empty());
+            if removed_all || removed_some {
+                stripped.push(category.name());
+            }
+        }
+        stripped
+    }
+
+    // the checkboxes this selection results in for an analyzed install. each removal goes through
+    // the same locks and rules as clicking its checkbox would, so no selection can get around them
+    pub fn keep_for(
+        &self,
+        availability: &CategoryAvailability,
+        advanced_selection: bool,
+    ) -> Vec<bool> {
+        let mut keep = availability.default_selection();
+
+        for (index, item) in availability.items.iter().enumerate() {
+            if !availability.is_item_enabled(index, advanced_selection)
+                || !self.is_removed(availability.category, &item.key)
+            {
+                continue;
+            }
+
+            if let Some(kept) = keep.get_mut(index) {
+                *kept = false;
+            }
+            availability.validate_selection(&mut keep, index, advanced_selection);
+        }
+
+        keep
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ShearPlanStep {
+    pub category: &'static dyn ShearCategory,
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ShearPlan {
+    pub steps: Vec<ShearPlanStep>,
+}
+
+impl ShearPlan {
+    pub fn new(categories: &[CategoryAvailability], selection: &[Vec<bool>]) -> Self {
+        let steps = categories
+            .iter()
+            .zip(selection)
+            .map(|(availability, keep)| ShearPlanStep {
+                category: availability.category,
+                paths: availability.category.plan(&availability.items, keep),
+            })
+            .filter(|step| !step.paths.is_empty())
+            .collect();
+
+        Self { steps }
+    }
+
+    // right before shearing, against what the install holds now
+    pub fn check(&self, folder: &Path) -> Result<(), String> {
+        self.steps.iter().try_for_each(|step| {
+            let items = step.category.detect(folder);
+            step.category.check_plan(&items, &step.paths)?;
+            step.category.check_files(folder, &items, &step.paths)
+        })
+    }
+
+    // while picking, against the analysis the plan was made from
+    pub fn check_analyzed(&self, categories: &[CategoryAvailability]) -> Result<(), String> {
+        self.steps.iter().try_for_each(|step| {
+            let items = categories
+                .iter()
+                .find(|availability| availability.category.id() == step.category.id())
+                .map_or(&[][..], |availability| availability.items.as_slice());
+            step.category.check_plan(items, &step.paths)
+        })
+    }
+
+    // runs every planned path through the guard before anything gets deleted
+    pub fn check_guard(&self, guard: &DeletionGuard) -> Result<(), String> {
+        let violations: Vec<String> = self
+            .steps
+            .iter()
+            .flat_map(|step| step.paths.iter())
+            .filter_map(|path| guard.check(path).err())
+            .map(|e| {
+                log::error!("Refusing to delete: {e}");
+                e.to_string()
+            })
+            .collect();
+
+        if violations.is_empty() {
+            return Ok(());
+        }
+
+        Err(format!(
+            "Shears refused to delete the following:\n{}",
+            violations.join("\n")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(category: &'static dyn ShearCategory, keys: &[&str]) -> CategoryAvailability {
+        let items = category
+            .known_items()
+            .into_iter()
+            .map(|mut item| {
+                if keys.contains(&item.key.as_str()) {
+                    item.size.apparent = 1;
+                }
+                item
+            })
+            .collect();
+
+        CategoryAvailability { category, items }
+    }
+
+    fn removing(category: &dyn ShearCategory, keys: &[&str]) -> ShearSelection {
+        let mut selection = ShearSelection::default();
+        selection.removed.insert(
+            category.id().to_owned(),
+            keys.iter().map(|&key| key.to_owned()).collect(),
+        );
+        selection
+    }
+
+    #[test]
+    fn keep_for_respects_locks() {
+        let textures = installed(&TexturesCategory, &["0", "1", "2"]);
+        let selection = removing(&TexturesCategory, &["0", "1", "2"]);
+
+        assert_eq!(
+            selection.keep_for(&textures, false),
+            vec![true, false, false, false, false],
+            "Low textures are locked"
+        );
+        assert_eq!(
+            selection.keep_for(&textures, true),
+            vec![false, false, false, false, false],
+            "advanced selection lifts the lock"
+        );
+    }
+
+    #[test]
+    fn keep_for_keeps_a_language() {
+        let languages = installed(&LanguagesCategory, &["en"]);
+        let selection = removing(&LanguagesCategory, &["de", "en", "fr"]);
+
+        let keep = selection.keep_for(&languages, false);
+        assert_eq!(keep.iter().filter(|&&kept| kept).count(), 1, "{keep:?}");
+        assert_eq!(
+            keep.first(),
+            Some(&true),
+            "English is the only installed language"
+        );
+    }
+
+    #[test]
+    fn keep_for_keeps_a_renderer() {
+        let renderers = installed(&RendererCategory, &["vulkan"]);
+        let selection = removing(&RendererCategory, &["dx11", "vulkan"]);
+
+        assert_eq!(selection.keep_for(&renderers, false), vec![true, false]);
+    }
+
+    fn with_paths(mut availability: CategoryAvailability) -> CategoryAvailability {
+        for item in availability
+            .items
+            .iter_mut()
+            .filter(|item| item.is_available())
+        {
+            item.paths = vec![PathBuf::from(format!("install/{}", item.key))];
+        }
+        availability
+    }
+
+    #[test]
+    fn renderer_plan_keeps_one() {
+        let renderers = with_paths(installed(&RendererCategory, &["vulkan", "dx11"]));
+        let plan = ShearPlan::new(std::slice::from_ref(&renderers), &[vec![false, false]]);
+
+        assert!(
+            plan.check_analyzed(&[renderers]).is_err(),
+            "every renderer can't be removed"
+        );
+    }
+
+    #[test]
+    fn language_plan_keeps_one() {
+        let languages = with_paths(installed(&LanguagesCategory, &["en", "fr"]));
+        let count = languages.items.len();
+        let plan = ShearPlan::new(std::slice::from_ref(&languages), &[vec![false; count]]);
+
+        assert!(
+            plan.check_analyzed(&[languages]).is_err(),
+            "every language can't be removed"
+        );
+    }
+
+    fn check_textures(keep: &[bool]) -> Result<(), String> {
+        let textures = with_paths(installed(&TexturesCategory, &["0", "1", "2", "3", "4"]));
+        let plan = ShearPlan::new(std::slice::from_ref(&textures), &[keep.to_vec()]);
+        plan.check_analyzed(&[textures])
+    }
+
+    #[test]
+    fn texture_plan_has_no_gaps() {
+        assert!(check_textures(&[true, false, true, false, false]).is_err());
+        assert!(check_textures(&[true, false, false, false, true]).is_err());
+        assert!(check_textures(&[false, true, true, false, true]).is_err());
+    }
+
+    #[test]
+    fn texture_plan_can_drop_lower_tiers() {
+        assert!(check_textures(&[true, true, true, false, false]).is_ok());
+        assert!(check_textures(&[false, false, true, true, true]).is_ok());
+        assert!(check_textures(&[false, false, true, false, false]).is_ok());
+        assert!(check_textures(&[false; 5]).is_err(), "one tier has to stay");
+    }
+}
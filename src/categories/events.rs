@@ -0,0 +1,111 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    categories::{CategoryItem, ShearCategory},
+    depgraph::{DependencyGraph, forge_stem},
+};
+
+#[derive(Debug)]
+pub struct EventsCategory;
+
+impl EventsCategory {
+    // `datapc64_events_y3s4_halloween` -> `y3s4_halloween`
+    fn event_name(stem: &str) -> String {
+        stem.split_once("events")
+            .map(|(_, name)| name.trim_matches('_'))
+            .filter(|name| !name.is_empty())
+            .unwrap_or(stem)
+            .to_owned()
+    }
+}
+
+impl ShearCategory for EventsCategory {
+    fn id(&self) -> &'static str {
+        "events"
+    }
+
+    fn name(&self) -> &'static str {
+        "Events"
+    }
+
+    fn is_redownloadable(&self) -> bool {
+        true
+    }
+
+    fn is_experimental(&self) -> bool {
+        true
+    }
+
+    // one item per event, grouping its forge with its dependency graph
+    fn detect(&self, folder: &Path) -> Vec<CategoryItem> {
+        let Ok(entries) = std::fs::read_dir(folder) else {
+            return Vec::new();
+        };
+
+        let mut events: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+
+            let Some(ext) = path.extension().and_then(|s| s.to_str()) else {
+                continue;
+            };
+
+            if !ext.eq_ignore_ascii_case("forge") && !ext.eq_ignore_ascii_case("depgraphbin") {
+                continue;
+            }
+
+            let Some(stem) = forge_stem(&path) else {
+                continue;
+            };
+
+            if !stem.contains("events") {
+                continue;
+            }
+
+            events.entry(stem).or_default().push(path);
+        }
+
+        events
+            .into_iter()
+            .map(|(stem, paths)| {
+                let mut item =
+                    CategoryItem::new(stem.clone(), format!("Event: {}", Self::event_name(&stem)));
+                item.paths = paths;
+                item
+            })
+            .collect()
+    }
+
+    // reads the dependency graph, so it only runs right before shearing
+    fn check_files(
+        &self,
+        folder: &Path,
+        _items: &[CategoryItem],
+        plan: &[PathBuf],
+    ) -> Result<(), String> {
+        let removed_stems: BTreeSet<String> = plan.iter().filter_map(|p| forge_stem(p)).collect();
+        if removed_stems.is_empty() {
+            return Ok(());
+        }
+
+        let dangling = DependencyGraph::load(folder)
+            .map_err(|e| format!("Shears can't tell what depends on the selected events: {e}"))?
+            .dangling_references(&removed_stems);
+        if dangling.is_empty() {
+            return Ok(());
+        }
+
+        let list = dangling
+            .iter()
+            .map(|(referrer, target)| format!("{referrer} depends on {target}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Err(format!(
+            "Removing the selected events would leave content that still depends on them:\n{list}"
+        ))
+    }
+}
@@ -0,0 +1,231 @@
+use std::path::{Path, PathBuf};
+
+use crate::categories::{CategoryItem, ShearCategory, check_keeps_one, keep_at_least_one};
+
+// (key, label, names used in forge name suffixes like `_fr.forge` or as folder names like `french(france)`)
+const LANGUAGES: [(&str, &str, &[&str]); 12] = [
+    ("en", "English", &["en", "en-us", "english", "english(us)"]),
+    ("fr", "French", &["fr", "fr-fr", "french", "french(france)"]),
+    ("de", "German", &["de", "de-de", "german"]),
+    (
+        "es",
+        "Spanish",
+        &["es", "es-es", "spanish", "spanish(spain)"],
+    ),
+    (
+        "mx",
+        "Spanish (Latin America)",
+        &["mx", "es-mx", "spanish(mexico)"],
+    ),
+    ("it", "Italian", &["it", "it-it", "italian"]),
+    (
+        "br",
+        "Portuguese (Brazil)",
+        &["br", "pt-br", "portuguese(brazil)"],
+    ),
+    ("ru", "Russian", &["ru", "ru-ru", "russian"]),
+    ("pl", "Polish", &["pl", "pl-pl", "polish"]),
+    ("ja", "Japanese", &["ja", "jp", "ja-jp", "japanese"]),
+    ("ko", "Korean", &["ko", "kr", "ko-kr", "korean"]),
+    (
+        "zh",
+        "Chinese",
+        &["zh", "cn", "tw", "zh-cn", "zh-tw", "chinese"],
+    ),
+];
+
+// voice-over banks live somewhere below it, in folders named after their language
+const SOUND_FOLDER: &str = "sounddata";
+
+#[derive(Debug)]
+pub struct LanguagesCategory;
+
+impl LanguagesCategory {
+    // two letter codes are only trusted as file suffixes, as folder names they are too common
+    fn language_index(name: &str, allow_codes: bool) -> Option<usize> {
+        LANGUAGES.iter().position(|&(_, _, names)| {
+            names
+                .iter()
+                .filter(|n| allow_codes || n.len() > 2)
+                .any(|n| n.eq_ignore_ascii_case(name))
+        })
+    }
+
+    // a forge at the root whose last `_` separated part is a language, e.g. `datapc64_..._fr.forge`
+    fn language_of_forge(path: &Path) -> Option<usize> {
+        let ext = path.extension().and_then(|s| s.to_str())?;
+        if !ext.eq_ignore_ascii_case("forge") {
+            return None;
+        }
+
+        let stem = path.file_stem().and_then(|s| s.to_str())?;
+        let (_, suffix) = stem.rsplit_once('_')?;
+        Self::language_index(suffix, true)
+    }
+
+    fn language_of_folder(path: &Path) -> Option<usize> {
+        let name = path.file_name().and_then(|s| s.to_str())?;
+        Self::language_index(name, false)
+    }
+
+    // only the root and the sound folder are looked at, game data elsewhere uses the same names
+    fn collect_root(root: &Path, items: &mut [CategoryItem]) {
+        let Ok(entries) = std::fs::read_dir(root) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_dir = entry.file_type().is_ok_and(|ft| ft.is_dir());
+
+            let language = if is_dir {
+                Self::language_of_folder(&path)
+            } else {
+                Self::language_of_forge(&path)
+            };
+            if let Some(item) = language.and_then(|i| items.get_mut(i)) {
+                item.paths.push(path);
+                continue;
+            }
+
+            if is_dir
+                && entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(SOUND_FOLDER))
+            {
+                Self::collect_sound_folders(&path, items);
+            }
+        }
+    }
+
+    fn collect_sound_folders(folder: &Path, items: &mut [CategoryItem]) {
+        let Ok(entries) = std::fs::read_dir(folder) else {
+            return;
+        };
+
+        let subfolders: Vec<PathBuf> = entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|ft| ft.is_dir()))
+            .map(|entry| entry.path())
+            .collect();
+        for subfolder in subfolders {
+            match Self::language_of_folder(&subfolder).and_then(|i| items.get_mut(i)) {
+                Some(item) => item.paths.push(subfolder),
+                None => Self::collect_sound_folders(&subfolder, items),
+            }
+        }
+    }
+}
+
+impl ShearCategory for LanguagesCategory {
+    fn id(&self) -> &'static str {
+        "languages"
+    }
+
+    fn name(&self) -> &'static str {
+        "Languages"
+    }
+
+    fn known_items(&self) -> Vec<CategoryItem> {
+        LANGUAGES
+            .iter()
+            .map(|&(key, label, _)| CategoryItem::new(key, format!("{label} language")))
+            .collect()
+    }
+
+    fn detect(&self, folder: &Path) -> Vec<CategoryItem> {
+        let mut items = self.known_items();
+
+        Self::collect_root(folder, &mut items);
+
+        // only list languages that are actually installed
+        items.retain(|item| !item.paths.is_empty());
+        items
+    }
+
+    // voice-over and text for at least one language are needed to play
+    fn validate_selection(&self, keep: &mut [bool], changed_index: usize) {
+        keep_at_least_one(keep, changed_index);
+    }
+
+    fn check_plan(&self, items: &[CategoryItem], plan: &[PathBuf]) -> Result<(), String> {
+        check_keeps_one(items, plan, "language")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(files: &[&str]) -> Vec<(String, Vec<String>)> {
+        let install = tempfile::tempdir().expect("Failed to create the test install");
+        for file in files {
+            let path = install.path().join(file);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).expect("Failed to create the test install");
+            }
+            std::fs::write(&path, "data").expect("Failed to create the test install");
+        }
+
+        LanguagesCategory
+            .detect(install.path())
+            .into_iter()
+            .map(|item| {
+                let mut paths: Vec<String> = item
+                    .paths
+                    .iter()
+                    .filter_map(|path| path.strip_prefix(install.path()).ok())
+                    .map(|path| path.to_string_lossy().replace('\\', "/"))
+                    .collect();
+                paths.sort();
+                (item.key, paths)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_localized_forges_and_sound_folders() {
+        let detected = detect(&[
+            "datapc64_merged_bnk_sound_en.forge",
+            "datapc64_merged_bnk_sound_fr.forge",
+            "sounddata/pc/english(us)/voices.pck",
+            "sounddata/pc/french(france)/voices.pck",
+            "german/strings.bin",
+        ]);
+
+        assert_eq!(
+            detected,
+            vec![
+                (
+                    "en".to_owned(),
+                    vec![
+                        "datapc64_merged_bnk_sound_en.forge".to_owned(),
+                        "sounddata/pc/english(us)".to_owned(),
+                    ]
+                ),
+                (
+                    "fr".to_owned(),
+                    vec![
+                        "datapc64_merged_bnk_sound_fr.forge".to_owned(),
+                        "sounddata/pc/french(france)".to_owned(),
+                    ]
+                ),
+                ("de".to_owned(), vec!["german".to_owned()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_game_data_with_language_like_names() {
+        let detected = detect(&[
+            "data/maps/house_it.forge",
+            "data/de/index.bin",
+            "pl/readme.txt",
+            "datapc64_build_it.bin",
+            "videos/english/intro.bik",
+        ]);
+
+        assert!(detected.is_empty(), "{detected:?}");
+    }
+}
@@ -0,0 +1,221 @@
+use std::path::Path;
+
+use crate::{
+    categories::{CategoryItem, ShearCategory},
+    journal::SHEARS_FOLDER_NAME,
+};
+
+struct LeftoverKind {
+    key: &'static str,
+    label: &'static str,
+    folders: &'static [&'static str], // only matched at the install root, the names are too common deeper down
+    file_prefixes: &'static [&'static str],
+    extensions: &'static [&'static str],
+}
+
+// files the game never needs to run, matched by well-known folder names at the root, and by file
+// names and extensions anywhere
+const LEFTOVER_KINDS: [LeftoverKind; 4] = [
+    LeftoverKind {
+        key: "redistributables",
+        label: "Redistributable installers",
+        folders: &["redist", "_commonredist", "directx", "vcredist"],
+        file_prefixes: &["vcredist", "vc_redist", "dxsetup", "dxwebsetup", "directx"],
+        extensions: &[],
+    },
+    LeftoverKind {
+        key: "crashdumps",
+        label: "Crash dumps",
+        folders: &["crashdumps", "crashes"],
+        file_prefixes: &[],
+        extensions: &["dmp", "mdmp"],
+    },
+    LeftoverKind {
+        key: "logs",
+        label: "Logs",
+        folders: &["logs"],
+        file_prefixes: &[],
+        extensions: &["log"],
+    },
+    LeftoverKind {
+        key: "debug",
+        label: "Debug files",
+        folders: &[],
+        file_prefixes: &[],
+        extensions: &["pdb"],
+    },
+];
+
+// folders that belong to other categories or to Shears itself
+const SKIPPED_FOLDERS: [&str; 2] = ["videos", SHEARS_FOLDER_NAME];
+
+#[derive(Debug)]
+pub struct LeftoversCategory;
+
+impl LeftoversCategory {
+    fn kind_of(path: &Path, is_dir: bool, is_root: bool) -> Option<usize> {
+        let name = path
+            .file_name()
+            .and_then(|s| s.to_str())?
+            .to_ascii_lowercase();
+
+        if is_dir {
+            if !is_root {
+                return None;
+            }
+            return LEFTOVER_KINDS
+                .iter()
+                .position(|kind| kind.folders.contains(&name.as_str()));
+        }
+
+        let extension = path
+            .extension()
+            .and_then(|s| s.to_str())
+            .map(str::to_ascii_lowercase)
+            .unwrap_or_default();
+
+        LEFTOVER_KINDS.iter().position(|kind| {
+            kind.extensions.contains(&extension.as_str())
+                || (extension == "exe"
+                    && kind
+                        .file_prefixes
+                        .iter()
+                        .any(|prefix| name.starts_with(prefix)))
+        })
+    }
+
+    fn collect(folder: &Path, items: &mut [CategoryItem], is_root: bool) {
+        let Ok(entries) = std::fs::read_dir(folder) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_dir = entry.file_type().is_ok_and(|ft| ft.is_dir());
+
+            if let Some(item) = Self::kind_of(&path, is_dir, is_root).and_then(|i| items.get_mut(i))
+            {
+                item.paths.push(path);
+                continue;
+            }
+
+            let skipped = is_root
+                && entry.file_name().to_str().is_some_and(|name| {
+                    SKIPPED_FOLDERS
+                        .iter()
+                        .any(|skipped| skipped.eq_ignore_ascii_case(name))
+                });
+            if is_dir && !skipped {
+                Self::collect(&path, items, false);
+            }
+        }
+    }
+}
+
+impl ShearCategory for LeftoversCategory {
+    fn id(&self) -> &'static str {
+        "leftovers"
+    }
+
+    fn name(&self) -> &'static str {
+        "Leftovers"
+    }
+
+    fn known_items(&self) -> Vec<CategoryItem> {
+        LEFTOVER_KINDS
+            .iter()
+            .map(|kind| CategoryItem::new(kind.key, kind.label))
+            .collect()
+    }
+
+    // the game never reads these, nothing is lost by removing them first
+    fn cut_priority(&self, _key: &str) -> Option<u32> {
+        Some(0)
+    }
+
+    fn detect(&self, folder: &Path) -> Vec<CategoryItem> {
+        let mut items = self.known_items();
+
+        Self::collect(folder, &mut items, true);
+
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(files: &[&str]) -> Vec<(String, Vec<String>)> {
+        let install = tempfile::tempdir().expect("Failed to create the test install");
+        for file in files {
+            let path = install.path().join(file);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).expect("Failed to create the test install");
+            }
+            std::fs::write(&path, "data").expect("Failed to create the test install");
+        }
+
+        LeftoversCategory
+            .detect(install.path())
+            .into_iter()
+            .map(|item| {
+                let mut paths: Vec<String> = item
+                    .paths
+                    .iter()
+                    .filter_map(|path| path.strip_prefix(install.path()).ok())
+                    .map(|path| path.to_string_lossy().replace('\\', "/"))
+                    .collect();
+                paths.sort();
+                (item.key, paths)
+            })
+            .collect()
+    }
+
+    fn paths_of<'a>(detected: &'a [(String, Vec<String>)], key: &str) -> &'a [String] {
+        detected
+            .iter()
+            .find(|(item, _)| item == key)
+            .map_or(&[], |(_, paths)| paths.as_slice())
+    }
+
+    #[test]
+    fn finds_leftover_folders_at_the_root() {
+        let detected = detect(&[
+            "_CommonRedist/vcredist_x64.exe",
+            "Logs/game.txt",
+            "crashdumps/1.bin",
+            "datapc64.forge",
+        ]);
+
+        assert_eq!(paths_of(&detected, "redistributables"), ["_CommonRedist"]);
+        assert_eq!(paths_of(&detected, "logs"), ["Logs"]);
+        assert_eq!(paths_of(&detected, "crashdumps"), ["crashdumps"]);
+    }
+
+    #[test]
+    fn keeps_game_folders_with_leftover_names() {
+        let detected = detect(&[
+            "support/localization.bin",
+            "data/logs/index.bin",
+            "data/crashes/handler.bin",
+            "data/directx/shaders.bin",
+        ]);
+
+        for (key, paths) in &detected {
+            assert!(paths.is_empty(), "{key}: {paths:?}");
+        }
+    }
+
+    #[test]
+    fn finds_leftover_files_anywhere() {
+        let detected = detect(&[
+            "data/symbols/game.pdb",
+            "BattlEye/install.log",
+            "data/game.dll",
+        ]);
+
+        assert_eq!(paths_of(&detected, "debug"), ["data/symbols/game.pdb"]);
+        assert_eq!(paths_of(&detected, "logs"), ["BattlEye/install.log"]);
+    }
+}
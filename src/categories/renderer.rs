@@ -0,0 +1,164 @@
+use std::path::{Path, PathBuf};
+
+use crate::categories::{CategoryItem, ShearCategory, check_keeps_one, keep_at_least_one};
+
+// (file name token, label), some builds ship an executable and shader files per renderer, named
+// like `RainbowSix_Vulkan.exe`. the token has to follow an underscore so runtime libraries such as
+// `d3dx11_43.dll` or `vulkan-1.dll` are left alone
+const RENDERER_VARIANTS: [(&str, &str); 2] = [("vulkan", "Vulkan"), ("dx11", "DirectX 11")];
+
+// the unsuffixed executable is the DirectX 11 renderer. it's part of the game's fingerprint and
+// protected, so DirectX 11 stays playable as long as none of its `_dx11` files are removed
+const DX11_EXECUTABLE: &str = "RainbowSix.exe";
+
+#[derive(Debug)]
+pub struct RendererCategory;
+
+impl RendererCategory {
+    fn keeps_dx11(items: &[CategoryItem], plan: &[PathBuf]) -> bool {
+        items
+            .iter()
+            .filter(|item| item.key == "dx11")
+            .all(|item| !item.paths.iter().any(|path| plan.contains(path)))
+    }
+}
+
+impl ShearCategory for RendererCategory {
+    fn id(&self) -> &'static str {
+        "renderer"
+    }
+
+    fn name(&self) -> &'static str {
+        "Renderers"
+    }
+
+    fn known_items(&self) -> Vec<CategoryItem> {
+        RENDERER_VARIANTS
+            .iter()
+            .map(|&(token, label)| CategoryItem::new(token, format!("{label} renderer")))
+            .collect()
+    }
+
+    fn detect(&self, folder: &Path) -> Vec<CategoryItem> {
+        let mut items = self.known_items();
+
+        let Ok(entries) = std::fs::read_dir(folder) else {
+            return items;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+
+            let Some(name) = path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .map(str::to_ascii_lowercase)
+            else {
+                continue;
+            };
+
+            if let Some(item) = items
+                .iter_mut()
+                .find(|item| name.contains(&format!("_{}", item.key)))
+            {
+                item.paths.push(path);
+            }
+        }
+
+        items
+    }
+
+    // the player needs at least one renderer to start the game
+    fn validate_selection(&self, keep: &mut [bool], changed_index: usize) {
+        keep_at_least_one(keep, changed_index);
+    }
+
+    // assumes the DirectX 11 executable is there, `check_files` makes sure before shearing
+    fn check_plan(&self, items: &[CategoryItem], plan: &[PathBuf]) -> Result<(), String> {
+        if Self::keeps_dx11(items, plan) {
+            return Ok(());
+        }
+
+        check_keeps_one(items, plan, "renderer")
+    }
+
+    fn check_files(
+        &self,
+        folder: &Path,
+        items: &[CategoryItem],
+        plan: &[PathBuf],
+    ) -> Result<(), String> {
+        if folder.join(DX11_EXECUTABLE).is_file() {
+            return Ok(());
+        }
+
+        check_keeps_one(items, plan, "renderer")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_install(files: &[&str]) -> (tempfile::TempDir, Vec<CategoryItem>) {
+        let install = tempfile::tempdir().expect("Failed to create the test install");
+        for file in files {
+            std::fs::write(install.path().join(file), "data")
+                .expect("Failed to create the test install");
+        }
+
+        let items = RendererCategory.detect(install.path());
+        (install, items)
+    }
+
+    fn paths_of(items: &[CategoryItem], keys: &[&str]) -> Vec<PathBuf> {
+        items
+            .iter()
+            .filter(|item| keys.contains(&item.key.as_str()))
+            .flat_map(|item| item.paths.iter().cloned())
+            .collect()
+    }
+
+    #[test]
+    fn unsuffixed_executable_keeps_dx11() {
+        let (install, items) = test_install(&["RainbowSix.exe", "RainbowSix_Vulkan.exe"]);
+        let plan = paths_of(&items, &["vulkan"]);
+
+        assert_eq!(plan.len(), 1);
+        assert!(RendererCategory.check_plan(&items, &plan).is_ok());
+        assert!(
+            RendererCategory
+                .check_files(install.path(), &items, &plan)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn refuses_removing_every_renderer() {
+        let (_install, items) = test_install(&[
+            "RainbowSix.exe",
+            "RainbowSix_Vulkan.exe",
+            "RainbowSix_DX11.exe",
+        ]);
+        let plan = paths_of(&items, &["vulkan", "dx11"]);
+
+        assert!(RendererCategory.check_plan(&items, &plan).is_err());
+        assert!(
+            RendererCategory
+                .check_plan(&items, &paths_of(&items, &["dx11"]))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn refuses_removing_the_only_renderer_without_the_dx11_executable() {
+        let (install, items) = test_install(&["RainbowSix_Vulkan.exe"]);
+        let plan = paths_of(&items, &["vulkan"]);
+
+        assert!(
+            RendererCategory
+                .check_files(install.path(), &items, &plan)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    categories::{CategoryItem, ShearCategory},
+    forge::read_forge,
+    types::ForgeTextureQualityLevel,
+};
+
+// the present tiers from Low up, with whether the plan keeps them
+fn present_tiers<'a>(
+    items: &'a [CategoryItem],
+    plan: &[PathBuf],
+) -> Vec<(ForgeTextureQualityLevel, &'a [PathBuf], bool)> {
+    items
+        .iter()
+        .filter(|item| !item.paths.is_empty())
+        .filter_map(|item| {
+            let level = ForgeTextureQualityLevel::convert_from_i32(item.key.parse().ok()?)?;
+            let kept = !item.paths.iter().any(|path| plan.contains(path));
+            Some((level, item.paths.as_slice(), kept))
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+pub struct TexturesCategory;
+
+impl TexturesCategory {
+    // texture forges are named like `datapc64_..._textures3.forge`, the digit being the quality level
+    pub fn quality_level_of(path: &Path) -> Option<ForgeTextureQualityLevel> {
+        let ext = path.extension().and_then(|s| s.to_str())?;
+        if !ext.eq_ignore_ascii_case("forge") {
+            return None;
+        }
+
+        let filename = path.file_stem().and_then(|s| s.to_str())?;
+        let (_, suffix) = filename.split_once("textures")?;
+        let level = suffix.chars().next().and_then(|c| c.to_digit(10))?;
+
+        ForgeTextureQualityLevel::convert_from_i32(level as i32)
+    }
+
+    // the one rule every texture check goes by. there is no build rule data to check against, so it is a
+    // heuristic: the game is assumed to use the lowest tier it finds as its base and stream every higher
+    // tier on top of the one below it. tiers below the base can go, but from the base up to the highest
+    // tier there can't be a gap. takes every tier from Low up with whether it is there, and returns the
+    // first missing tier inside that range, with the highest tier that builds on it
+    pub fn find_tier_gap(
+        tiers: impl IntoIterator<Item = (ForgeTextureQualityLevel, bool)>,
+    ) -> Option<(ForgeTextureQualityLevel, ForgeTextureQualityLevel)> {
+        let tiers: Vec<(ForgeTextureQualityLevel, bool)> = tiers.into_iter().collect();
+        let mut present = tiers.iter().filter(|&&(_, present)| present);
+        let &(lowest, _) = present.next()?;
+        let &(highest, _) = present.next_back()?;
+
+        tiers
+            .iter()
+            .find(|&&(level, present)| !present && level > lowest && level < highest)
+            .map(|&(gap, _)| (gap, highest))
+    }
+}
+
+impl ShearCategory for TexturesCategory {
+    fn id(&self) -> &'static str {
+        "textures"
+    }
+
+    fn name(&self) -> &'static str {
+        "Textures"
+    }
+
+    fn is_redownloadable(&self) -> bool {
+        true
+    }
+
+    fn known_items(&self) -> Vec<CategoryItem> {
+        (ForgeTextureQualityLevel::Low.convert_to_i32()
+            ..=ForgeTextureQualityLevel::Ultra.convert_to_i32())
+            .filter_map(ForgeTextureQualityLevel::convert_from_i32)
+            .map(|level| {
+                CategoryItem::new(
+                    level.convert_to_i32().to_string(),
+                    format!("{level} Textures"),
+                )
+            })
+            .collect()
+    }
+
+    // tiers are cut from the top down with videos slotted in after Ultra, Low is never cut
+    fn cut_priority(&self, key: &str) -> Option<u32> {
+        match ForgeTextureQualityLevel::convert_from_i32(key.parse().ok()?)? {
+            ForgeTextureQualityLevel::Ultra => Some(10),
+            ForgeTextureQualityLevel::VeryHigh => Some(30),
+            ForgeTextureQualityLevel::High => Some(50),
+            ForgeTextureQualityLevel::Medium => Some(70),
+            ForgeTextureQualityLevel::Low => None,
+        }
+    }
+
+    fn detect(&self, folder: &Path) -> Vec<CategoryItem> {
+        let mut items = self.known_items();
+
+        let Ok(entries) = std::fs::read_dir(folder) else {
+            return items;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+
+            let Some(quality_level) = Self::quality_level_of(&path) else {
+                continue;
+            };
+
+            items
+                .get_mut(quality_level as usize)
+                .expect("TexturesCategory.detect: Out of bounds error")
+                .paths
+                .push(path);
+        }
+
+        items
+    }
+
+    fn is_locked(&self, item_index: usize) -> bool {
+        // let's avoid users making their games completely unplayable
+        item_index == ForgeTextureQualityLevel::Low as usize
+    }
+
+    fn supports_advanced_selection(&self) -> bool {
+        true
+    }
+
+    // the compatibility check behind the advanced selection, goes by `find_tier_gap`
+    fn check_plan(&self, items: &[CategoryItem], plan: &[PathBuf]) -> Result<(), String> {
+        let tiers = present_tiers(items, plan);
+        if tiers.is_empty() {
+            return Ok(());
+        }
+        if !tiers.iter().any(|&(_, _, kept)| kept) {
+            return Err("At least one texture quality level has to be kept.".to_owned());
+        }
+
+        if let Some((gap, highest)) =
+            Self::find_tier_gap(tiers.iter().map(|&(level, _, kept)| (level, kept)))
+        {
+            return Err(format!(
+                "{highest} textures build on top of {gap} textures. Keep {gap} textures too, or remove every tier above them."
+            ));
+        }
+
+        Ok(())
+    }
+
+    // the lowest kept tier becomes the base once the ones below it are removed. only the header and
+    // entry tables of its forges are checked, an intact table doesn't prove the textures themselves are fine
+    fn check_files(
+        &self,
+        _folder: &Path,
+        items: &[CategoryItem],
+        plan: &[PathBuf],
+    ) -> Result<(), String> {
+        let tiers = present_tiers(items, plan);
+        let Some(&(lowest_kept, paths, _)) = tiers.iter().find(|&&(_, _, kept)| kept) else {
+            return Ok(());
+        };
+        if tiers
+            .first()
+            .is_some_and(|&(lowest, _, _)| lowest == lowest_kept)
+        {
+            return Ok(());
+        }
+
+        for path in paths {
+            if let Err(e) = read_forge(path) {
+                return Err(format!(
+                    "{lowest_kept} textures would replace the removed lower tiers, but {} is damaged ({e}).",
+                    path.display()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    // makes sure you cant select for example, high without low, and so on up to ultra
+    fn validate_selection(&self, keep: &mut [bool], changed_index: usize) {
+        if changed_index == ForgeTextureQualityLevel::Low as usize {
+            return;
+        }
+
+        let ultra_idx = ForgeTextureQualityLevel::Ultra as usize;
+        if changed_index < ultra_idx {
+            keep.get_mut((changed_index + 1)..=ultra_idx)
+                .expect("Out of bounds error")
+                .fill(false);
+        }
+
+        if *keep.get(changed_index).expect("Out of bounds error") {
+            let medium_idx = ForgeTextureQualityLevel::Medium as usize;
+            keep.get_mut(medium_idx..changed_index)
+                .expect("Out of bounds error")
+                .fill(true);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use std::path::Path;
+
+use crate::categories::{CategoryItem, ShearCategory};
+
+#[derive(Debug)]
+pub struct VideosCategory;
+
+impl ShearCategory for VideosCategory {
+    fn id(&self) -> &'static str {
+        "videos"
+    }
+
+    fn name(&self) -> &'static str {
+        "Videos"
+    }
+
+    fn is_redownloadable(&self) -> bool {
+        true
+    }
+
+    fn known_items(&self) -> Vec<CategoryItem> {
+        vec![CategoryItem::new("videos", "Videos")]
+    }
+
+    fn cut_priority(&self, _key: &str) -> Option<u32> {
+        Some(20)
+    }
+
+    fn detect(&self, folder: &Path) -> Vec<CategoryItem> {
+        let mut items = self.known_items();
+
+        let video_sub_folder = folder.join("videos");
+        if video_sub_folder.is_dir()
+            && let Some(item) = items.first_mut()
+        {
+            item.paths.push(video_sub_folder);
+        }
+
+        items
+    }
+}
@@ -0,0 +1,78 @@
+use std::io::Write as _;
+
+use crate::forge::read_forge;
+
+const USAGE: &str = "Usage: shears-cli inspect-forge <file.forge>...";
+
+// returns the exit code of the command
+pub fn run_cli(args: &[String]) -> i32 {
+    let Some((command, rest)) = args.split_first() else {
+        write_stderr(&format!("{USAGE}\n"));
+        return 2;
+    };
+
+    match command.as_str() {
+        "inspect-forge" => inspect_forge(rest),
+        "help" | "--help" | "-h" => {
+            write_stdout(&format!("{USAGE}\n"));
+            0
+        }
+        _ => {
+            write_stderr(&format!("Unknown command `{command}`\n{USAGE}\n"));
+            2
+        }
+    }
+}
+
+fn write_stdout(text: &str) {
+    if let Err(e) = std::io::stdout().lock().write_all(text.as_bytes()) {
+        log::error!("Failed to write to stdout: {e}");
+    }
+}
+
+fn write_stderr(text: &str) {
+    if let Err(e) = std::io::stderr().lock().write_all(text.as_bytes()) {
+        log::error!("Failed to write to stderr: {e}");
+    }
+}
+
+fn inspect_forge(paths: &[String]) -> i32 {
+    if paths.is_empty() {
+        write_stderr(&format!("{USAGE}\n"));
+        return 2;
+    }
+
+    let mut exit_code = 0;
+    for path in paths {
+        let forge = match read_forge(std::path::Path::new(path)) {
+            Ok(forge) => forge,
+            Err(e) => {
+                write_stderr(&format!("{path}: {e}\n"));
+                exit_code = 1;
+                continue;
+            }
+        };
+
+        let mut output = format!(
+            "{path}\n  version {}, {} entries, {} of {} in entries\n",
+            forge.version,
+            forge.entries.len(),
+            humansize::format_size(forge.total_entry_size(), humansize::WINDOWS),
+            humansize::format_size(forge.file_len, humansize::WINDOWS),
+        );
+
+        output.push_str(&format!("  {:<12} {:>8} {:>12}\n", "type", "count", "size"));
+        for summary in forge.summarize_by_type() {
+            output.push_str(&format!(
+                "  0x{:08X}   {:>8} {:>12}\n",
+                summary.entry_type,
+                summary.count,
+                humansize::format_size(summary.size, humansize::WINDOWS)
+            ));
+        }
+
+        write_stdout(&output);
+    }
+
+    exit_code
+}
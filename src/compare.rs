@@ -0,0 +1,363 @@
+use std::{
+    collections::BTreeMap,
+    hash::Hasher as _,
+    io::Read as _,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    categories::{
+        CategoryAvailability, ShearCategory, ShearSelection, TexturesCategory, VideosCategory,
+    },
+    helpers::get_shearing_features_availability,
+    journal::SHEARS_FOLDER_NAME,
+    progress::JobProgress,
+    sizecache::SizeCache,
+    types::ShearingFeaturesAvailability,
+};
+
+const OTHER_GROUP: &str = "Other";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileDiffKind {
+    OnlyInA(u64),
+    OnlyInB(u64),
+    SizeDiffers { a: u64, b: u64 },
+    ContentDiffers(u64),
+}
+
+#[derive(Clone, Debug)]
+pub struct FileDiff {
+    pub path: PathBuf, // relative to the install folder
+    pub kind: FileDiffKind,
+}
+
+// the differences that fall into one Shears category or item, e.g. "Ultra Textures" or "Videos"
+#[derive(Clone, Debug, Default)]
+pub struct DiffGroup {
+    pub name: String,
+    pub files: Vec<FileDiff>,
+}
+
+#[derive(Debug)]
+pub struct InstallComparison {
+    pub a: PathBuf,
+    pub b: PathBuf,
+    pub features_a: ShearingFeaturesAvailability,
+    pub features_b: ShearingFeaturesAvailability,
+    pub groups: Vec<DiffGroup>,
+    pub compared_contents: bool,
+}
+
+impl InstallComparison {
+    pub fn is_identical(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    // what has to be removed from `from` so it ends up at the same shear level as `to`. the level is
+    // the texture tiers and videos, languages, renderers and events differ between installs on purpose
+    pub fn match_selection(
+        from: &ShearingFeaturesAvailability,
+        to: &ShearingFeaturesAvailability,
+    ) -> ShearSelection {
+        let mut selection = ShearSelection::default();
+
+        for availability in from.categories.iter().filter(|availability| {
+            [TexturesCategory.id(), VideosCategory.id()].contains(&availability.category.id())
+        }) {
+            let target = to
+                .categories
+                .iter()
+                .find(|target| target.category.id() == availability.category.id());
+
+            for item in availability.items.iter().filter(|item| item.is_available()) {
+                let kept_in_target = target.is_some_and(|target| {
+                    target
+                        .items
+                        .iter()
+                        .any(|other| other.key == item.key && other.is_available())
+                });
+                if !kept_in_target {
+                    selection
+                        .removed
+                        .entry(availability.category.id().to_owned())
+                        .or_default()
+                        .insert(item.key.clone());
+                }
+            }
+        }
+
+        selection
+    }
+}
+
+// textures and other categories with fixed items are split per item, the rest is grouped per category
+fn group_name(category: &dyn ShearCategory, item_label: &str) -> String {
+    if category.known_items().len() > 1 {
+        item_label.to_owned()
+    } else {
+        category.name().to_owned()
+    }
+}
+
+// relative item paths of both installs, each with the name of the group it belongs to
+fn group_paths(
+    installs: [(&Path, &[CategoryAvailability]); 2],
+) -> (Vec<String>, Vec<(PathBuf, usize)>) {
+    let mut names: Vec<String> = Vec::new();
+    let mut paths: Vec<(PathBuf, usize)> = Vec::new();
+
+    for (root, categories) in installs {
+        for availability in categories {
+            for item in &availability.items {
+                let name = group_name(availability.category, &item.label);
+                let index = if let Some(index) = names.iter().position(|known| *known == name) {
+                    index
+                } else {
+                    names.push(name);
+                    names.len() - 1
+                };
+
+                paths.extend(
+                    item.paths
+                        .iter()
+                        .filter_map(|path| path.strip_prefix(root).ok())
+                        .map(|path| (path.to_path_buf(), index)),
+                );
+            }
+        }
+    }
+
+    names.push(OTHER_GROUP.to_owned());
+    (names, paths)
+}
+
+fn list_files(
+    root: &Path,
+    folder: &Path,
+    files: &mut BTreeMap<PathBuf, u64>,
+    progress: &JobProgress,
+) {
+    if progress.is_cancelled() {
+        return;
+    }
+
+    let entries = match std::fs::read_dir(folder) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("Unable to list {}: {e}", folder.display());
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            log::warn!("Unable to read {}", path.display());
+            continue;
+        };
+
+        if metadata.is_dir() {
+            // shears' own staging and ledger files aren't part of the game
+            if folder == root && entry.file_name() == SHEARS_FOLDER_NAME {
+                continue;
+            }
+            list_files(root, &path, files, progress);
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            files.insert(relative.to_path_buf(), metadata.len());
+        }
+    }
+}
+
+// not cryptographic, only meant to tell two copies of a file apart
+fn hash_file(path: &Path, progress: &JobProgress) -> std::io::Result<u64> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = std::hash::DefaultHasher::new();
+    let mut buffer = vec![0_u8; 1024 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 || progress.is_cancelled() {
+            break;
+        }
+        hasher.write(buffer.get(..read).expect("hash_file: Out of bounds error"));
+    }
+
+    Ok(hasher.finish())
+}
+
+fn contents_differ(a: &Path, b: &Path, progress: &JobProgress) -> bool {
+    match (hash_file(a, progress), hash_file(b, progress)) {
+        (Ok(hash_a), Ok(hash_b)) => hash_a != hash_b,
+        (Err(e), _) | (_, Err(e)) => {
+            log::warn!("Unable to compare {}: {e}", a.display());
+            true
+        }
+    }
+}
+
+fn diff_files(a: &Path, b: &Path, compare_contents: bool, progress: &JobProgress) -> Vec<FileDiff> {
+    let mut files_a = BTreeMap::new();
+    let mut files_b = BTreeMap::new();
+    progress.set_stage("Listing files");
+    list_files(a, a, &mut files_a, progress);
+    list_files(b, b, &mut files_b, progress);
+
+    let mut diffs: Vec<FileDiff> = files_a
+        .iter()
+        .filter(|(path, _)| !files_b.contains_key(*path))
+        .map(|(path, &len)| FileDiff {
+            path: path.clone(),
+            kind: FileDiffKind::OnlyInA(len),
+        })
+        .chain(
+            files_b
+                .iter()
+                .filter(|(path, _)| !files_a.contains_key(*path))
+                .map(|(path, &len)| FileDiff {
+                    path: path.clone(),
+                    kind: FileDiffKind::OnlyInB(len),
+                }),
+        )
+        .collect();
+
+    progress.set_stage(if compare_contents {
+        "Comparing file contents"
+    } else {
+        "Comparing file sizes"
+    });
+    for (path, &len_a) in &files_a {
+        let Some(&len_b) = files_b.get(path) else {
+            continue;
+        };
+        if progress.is_cancelled() {
+            break;
+        }
+
+        if len_a != len_b {
+            diffs.push(FileDiff {
+                path: path.clone(),
+                kind: FileDiffKind::SizeDiffers { a: len_a, b: len_b },
+            });
+        } else if compare_contents {
+            progress.set_current(path.display().to_string());
+            if contents_differ(&a.join(path), &b.join(path), progress) {
+                diffs.push(FileDiff {
+                    path: path.clone(),
+                    kind: FileDiffKind::ContentDiffers(len_a),
+                });
+            }
+        }
+    }
+
+    diffs
+}
+
+// lists every file that differs between two installs, grouped by the Shears category it belongs to.
+// hashing is optional because it reads both installs in full
+pub fn compare_installs(
+    a: &Path,
+    b: &Path,
+    categories: &[&'static dyn ShearCategory],
+    compare_contents: bool,
+    cache: &SizeCache,
+    progress: &JobProgress,
+) -> InstallComparison {
+    progress.set_stage("Analyzing installs");
+    let features_a = get_shearing_features_availability(a, categories, cache, progress);
+    let features_b = get_shearing_features_availability(b, categories, cache, progress);
+
+    let (names, item_paths) = group_paths([
+        (a, features_a.categories.as_slice()),
+        (b, features_b.categories.as_slice()),
+    ]);
+    let mut groups: Vec<DiffGroup> = names
+        .into_iter()
+        .map(|name| DiffGroup {
+            name,
+            files: Vec::new(),
+        })
+        .collect();
+    let other_index = groups.len() - 1;
+
+    for diff in diff_files(a, b, compare_contents, progress) {
+        let index = item_paths
+            .iter()
+            .find(|(item_path, _)| diff.path.starts_with(item_path))
+            .map_or(other_index, |&(_, index)| index);
+
+        if let Some(group) = groups.get_mut(index) {
+            group.files.push(diff);
+        }
+    }
+
+    groups.retain(|group| !group.files.is_empty());
+
+    InstallComparison {
+        a: a.to_path_buf(),
+        b: b.to_path_buf(),
+        features_a,
+        features_b,
+        groups,
+        compared_contents: compare_contents,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::categories::{CategoryItem, LanguagesCategory};
+
+    fn features(
+        categories: &[(&'static dyn ShearCategory, &[&str])],
+    ) -> ShearingFeaturesAvailability {
+        ShearingFeaturesAvailability {
+            categories: categories
+                .iter()
+                .map(|&(category, keys)| CategoryAvailability {
+                    category,
+                    items: keys
+                        .iter()
+                        .map(|&key| {
+                            let mut item = CategoryItem::new(key, key);
+                            item.size.apparent = 1;
+                            item
+                        })
+                        .collect(),
+                })
+                .collect(),
+            ..ShearingFeaturesAvailability::default()
+        }
+    }
+
+    #[test]
+    fn match_selection_only_matches_the_shear_level() {
+        let english = features(&[
+            (&TexturesCategory, &["0", "1", "2", "3"]),
+            (&VideosCategory, &["videos"]),
+            (&LanguagesCategory, &["en"]),
+        ]);
+        let french = features(&[
+            (&TexturesCategory, &["0", "1", "2"]),
+            (&LanguagesCategory, &["fr"]),
+        ]);
+
+        let selection = InstallComparison::match_selection(&english, &french);
+        assert!(
+            selection.is_removed(&TexturesCategory, "3"),
+            "{selection:?}"
+        );
+        assert!(
+            selection.is_removed(&VideosCategory, "videos"),
+            "{selection:?}"
+        );
+        assert!(
+            !selection.is_removed(&TexturesCategory, "2"),
+            "{selection:?}"
+        );
+        assert!(
+            !selection.is_removed(&LanguagesCategory, "en"),
+            "{selection:?}"
+        );
+    }
+}
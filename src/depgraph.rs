@@ -0,0 +1,184 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+// `.depgraphbin` files list the forge containers a piece of content depends on. the graph isn't
+// decoded, every `datapc64...` name it mentions is pulled out instead. that only works as long as the
+// names are stored as plain ASCII, so a depgraph that yields no names at all is treated as unreadable
+// rather than as one without references
+const FORGE_NAME_PREFIX: &str = "datapc64";
+const MIN_NAME_LEN: usize = FORGE_NAME_PREFIX.len();
+
+pub fn forge_stem(path: &Path) -> Option<String> {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .map(str::to_ascii_lowercase)
+}
+
+fn is_name_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'.' || byte == b'-'
+}
+
+fn extract_forge_names(data: &[u8]) -> BTreeSet<String> {
+    data.split(|&byte| !is_name_byte(byte))
+        .filter(|run| run.len() >= MIN_NAME_LEN)
+        .filter_map(|run| std::str::from_utf8(run).ok())
+        .map(str::to_ascii_lowercase)
+        // a length or id byte right before a name can look like part of it
+        .filter_map(|run| {
+            run.find(FORGE_NAME_PREFIX)
+                .and_then(|start| run.get(start..))
+                .map(str::to_owned)
+        })
+        .map(|name| {
+            // references may or may not carry an extension, normalize them to stems
+            name.strip_suffix(".forge")
+                .or_else(|| name.strip_suffix(".depgraphbin"))
+                .map(str::to_owned)
+                .unwrap_or(name)
+        })
+        .collect()
+}
+
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+    // depgraph stem -> forge stems it references
+    references: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl DependencyGraph {
+    // fails as a whole when any depgraph can't be read, a missing graph would hide its references
+    pub fn load(folder: &Path) -> Result<Self, String> {
+        let mut graph = Self::default();
+
+        let entries = std::fs::read_dir(folder)
+            .map_err(|e| format!("{} could not be listed ({e})", folder.display()))?;
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+
+            if !path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("depgraphbin"))
+            {
+                continue;
+            }
+
+            let Some(stem) = forge_stem(&path) else {
+                continue;
+            };
+
+            let data = std::fs::read(&path)
+                .map_err(|e| format!("{} could not be read ({e})", path.display()))?;
+            let mut names = extract_forge_names(&data);
+            if names.is_empty() {
+                return Err(format!(
+                    "{} doesn't mention any forge by name, its format isn't one Shears understands",
+                    path.display()
+                ));
+            }
+
+            names.remove(&stem);
+            graph.references.insert(stem, names);
+        }
+
+        Ok(graph)
+    }
+
+    // every (referrer, removed forge) pair where content that stays would point at a removed forge
+    pub fn dangling_references(&self, removed_stems: &BTreeSet<String>) -> Vec<(String, String)> {
+        self.references
+            .iter()
+            .filter(|&(referrer, _)| !removed_stems.contains(referrer))
+            .flat_map(|(referrer, targets)| {
+                targets
+                    .intersection(removed_stems)
+                    .map(move |target| (referrer.clone(), target.clone()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // no real depgraph can be shipped here, these samples are built the way Shears assumes the
+    // format to be: length prefixed names between binary fields
+    fn depgraph(names: &[&str]) -> Vec<u8> {
+        let mut data = vec![0x47, 0x52, 0x50, 0x48, 0x01, 0x00, 0x00, 0x00];
+        for name in names {
+            data.extend_from_slice(&u32::try_from(name.len()).unwrap_or(0).to_le_bytes());
+            data.extend_from_slice(name.as_bytes());
+            data.extend_from_slice(&[0x00, 0xff, 0x10, 0x00]);
+        }
+        data
+    }
+
+    fn test_folder(files: &[(&str, Vec<u8>)]) -> tempfile::TempDir {
+        let folder = tempfile::tempdir().expect("Failed to create the test folder");
+        for (file, data) in files {
+            std::fs::write(folder.path().join(file), data).expect("Failed to write a test file");
+        }
+        folder
+    }
+
+    #[test]
+    fn extracts_referenced_forges() {
+        let data = depgraph(&[
+            "datapc64_events_y3s4_halloween.depgraphbin",
+            "datapc64_merged_bnk_textures3.forge",
+            "DATAPC64_Events_Shared",
+        ]);
+
+        let names = extract_forge_names(&data);
+        assert_eq!(
+            names,
+            BTreeSet::from([
+                "datapc64_events_shared".to_owned(),
+                "datapc64_events_y3s4_halloween".to_owned(),
+                "datapc64_merged_bnk_textures3".to_owned(),
+            ])
+        );
+    }
+
+    #[test]
+    fn extracts_names_glued_to_a_prefix() {
+        let names = extract_forge_names(b"\x01\x00dDATAPC64_events_shared\x00");
+        assert_eq!(names, BTreeSet::from(["datapc64_events_shared".to_owned()]));
+    }
+
+    #[test]
+    fn finds_dangling_references() {
+        let folder = test_folder(&[
+            (
+                "datapc64_main.depgraphbin",
+                depgraph(&["datapc64_main", "datapc64_events_shared"]),
+            ),
+            (
+                "datapc64_events_shared.depgraphbin",
+                depgraph(&["datapc64_events_shared"]),
+            ),
+        ]);
+
+        let graph = DependencyGraph::load(folder.path()).expect("The samples should load");
+        let removed = BTreeSet::from(["datapc64_events_shared".to_owned()]);
+        assert_eq!(
+            graph.dangling_references(&removed),
+            vec![(
+                "datapc64_main".to_owned(),
+                "datapc64_events_shared".to_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn refuses_depgraphs_without_names() {
+        // what a format storing hashes instead of names would look like
+        let hashes: Vec<u8> = (0_u8..64).map(|i| i.wrapping_mul(37)).collect();
+        let folder = test_folder(&[("datapc64_main.depgraphbin", hashes)]);
+
+        assert!(DependencyGraph::load(folder.path()).is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use std::path::{Path, PathBuf};
+
+// "apparent" is what the file lengths add up to, "reclaimable" is the space the drive actually gets back:
+// allocated blocks, and nothing for files that are still hard linked somewhere else
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct DiskUsage {
+    pub apparent: u64,
+    pub reclaimable: u64,
+    pub linked_files: u64,
+}
+
+impl std::ops::Add for DiskUsage {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            apparent: self.apparent + other.apparent,
+            reclaimable: self.reclaimable + other.reclaimable,
+            linked_files: self.linked_files + other.linked_files,
+        }
+    }
+}
+
+impl std::iter::Sum for DiskUsage {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |total, usage| total + usage)
+    }
+}
+
+// without unsafe code there is no way to ask Windows for link counts or allocated sizes, so
+// "reclaimable" is worked out from the file length there and hard links are never noticed
+pub const RECLAIMABLE_IS_ESTIMATE: bool = cfg!(not(unix));
+
+pub fn format_reclaimable(bytes: u64) -> String {
+    let size = humansize::format_size(bytes, humansize::WINDOWS);
+    if RECLAIMABLE_IS_ESTIMATE {
+        format!("about {size}")
+    } else {
+        size
+    }
+}
+
+impl std::fmt::Display for DiskUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, {} reclaimable",
+            humansize::format_size(self.apparent, humansize::WINDOWS),
+            format_reclaimable(self.reclaimable)
+        )
+    }
+}
+
+impl DiskUsage {
+    pub fn of_file(metadata: &std::fs::Metadata) -> Self {
+        let linked = link_count(metadata) > 1;
+
+        Self {
+            apparent: metadata.len(),
+            reclaimable: if linked { 0 } else { allocated_size(metadata) },
+            linked_files: u64::from(linked),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn allocated_size(metadata: &std::fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt as _;
+    metadata.blocks() * 512 // st_blocks is always in 512 byte units
+}
+
+// an estimate, assumes the default 4 KiB NTFS cluster and ignores compression and sparse files
+#[cfg(not(unix))]
+fn allocated_size(metadata: &std::fs::Metadata) -> u64 {
+    metadata.len().div_ceil(4096) * 4096
+}
+
+#[cfg(unix)]
+fn link_count(metadata: &std::fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt as _;
+    metadata.nlink()
+}
+
+#[cfg(not(unix))]
+fn link_count(_metadata: &std::fs::Metadata) -> u64 {
+    1
+}
+
+// entries that can't be read are skipped and collected in `unreadable` instead of failing the whole path
+pub fn path_usage(path: &Path, unreadable: &mut Vec<(PathBuf, String)>) -> DiskUsage {
+    let metadata = match std::fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            unreadable.push((path.to_path_buf(), e.to_string()));
+            return DiskUsage::default();
+        }
+    };
+
+    if !metadata.is_dir() {
+        return DiskUsage::of_file(&metadata);
+    }
+
+    let entries = match std::fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            unreadable.push((path.to_path_buf(), e.to_string()));
+            return DiskUsage::default();
+        }
+    };
+
+    let mut usage = DiskUsage::default();
+    for entry in entries {
+        match entry {
+            Ok(entry) => usage = usage + path_usage(&entry.path(), unreadable),
+            Err(e) => unreadable.push((path.to_path_buf(), e.to_string())),
+        }
+    }
+    usage
+}
@@ -0,0 +1,172 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    categories::{ShearPlan, find_category},
+    helpers::{get_file_size, get_path_size},
+    ledger::ShearLedger,
+    streaminginstall::STREAMING_INSTALL_FILE_NAME,
+};
+
+// a DepotDownloader `-filelist`: one depot path per line, folders become `regex:` lines matching
+// everything below them since their contents are gone once sheared
+#[derive(Clone, Debug, Default)]
+pub struct DepotFileList {
+    pub files: BTreeMap<PathBuf, u64>, // relative to the install, with the size they had
+    pub folders: BTreeMap<PathBuf, u64>,
+}
+
+// depot paths always use forward slashes
+fn depot_path(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+impl DepotFileList {
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.folders.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len() + self.folders.len()
+    }
+
+    // uncompressed, the download itself is usually smaller
+    pub fn size(&self) -> u64 {
+        self.files.values().chain(self.folders.values()).sum()
+    }
+
+    // Shears rewrites streaminginstall.ini, the original has to come back with the files it lists
+    fn add_streaming_install(&mut self, root: &Path) {
+        if self.is_empty() {
+            return;
+        }
+
+        let size = get_file_size(&root.join(STREAMING_INSTALL_FILE_NAME)).unwrap_or(0);
+        self.files
+            .insert(PathBuf::from(STREAMING_INSTALL_FILE_NAME), size);
+    }
+
+    // everything the ledger says was removed that is still missing, files brought back by a
+    // verify in the meantime are left out
+    pub fn from_ledger(root: &Path, ledger: &ShearLedger) -> Self {
+        let mut list = Self::default();
+
+        for file in ledger.shears.iter().flat_map(|entry| &entry.files) {
+            let redownloadable =
+                find_category(&file.category).is_some_and(|category| category.is_redownloadable());
+            if !redownloadable || root.join(&file.path).exists() {
+                continue;
+            }
+
+            let paths = if file.folder {
+                &mut list.folders
+            } else {
+                &mut list.files
+            };
+            paths.insert(file.path.clone(), file.size);
+        }
+
+        list.add_streaming_install(root);
+        list
+    }
+
+    // what a shear with this plan removes, to keep around before shearing
+    pub fn from_plan(root: &Path, plan: &ShearPlan) -> Self {
+        let mut list = Self::default();
+
+        for step in plan
+            .steps
+            .iter()
+            .filter(|step| step.category.is_redownloadable())
+        {
+            for path in &step.paths {
+                let Ok(relative) = path.strip_prefix(root) else {
+                    continue;
+                };
+
+                let paths = if path.is_dir() {
+                    &mut list.folders
+                } else {
+                    &mut list.files
+                };
+                paths.insert(relative.to_path_buf(), get_path_size(path));
+            }
+        }
+
+        list.add_streaming_install(root);
+        list
+    }
+
+    pub fn contents(&self) -> String {
+        let files = self.files.keys().map(|path| depot_path(path));
+        let folders = self
+            .folders
+            .keys()
+            .map(|path| format!("regex:^{}/", escape_regex(&depot_path(path))));
+
+        files
+            .chain(folders)
+            .fold(String::new(), |mut contents, line| {
+                contents.push_str(&line);
+                contents.push('\n');
+                contents
+            })
+    }
+
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.contents())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_regex_characters() {
+        assert_eq!(escape_regex("videos"), "videos");
+        assert_eq!(
+            escape_regex("a.b+c*d?e(f)g|h[i]j{k}l^m$n\\o"),
+            "a\\.b\\+c\\*d\\?e\\(f\\)g\\|h\\[i\\]j\\{k\\}l\\^m\\$n\\\\o"
+        );
+    }
+
+    #[test]
+    fn depot_paths_use_forward_slashes() {
+        let path: PathBuf = ["sounds", "english", "events"].iter().collect();
+        assert_eq!(depot_path(&path), "sounds/english/events");
+    }
+
+    #[test]
+    fn lists_files_then_folders() {
+        let mut list = DepotFileList::default();
+        list.files
+            .insert(PathBuf::from("datapc64_textures4.forge"), 10);
+        list.files.insert(PathBuf::from("streaminginstall.ini"), 1);
+        list.folders
+            .insert(["videos", "4k (hd)"].iter().collect(), 100);
+
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.size(), 111);
+        assert_eq!(
+            list.contents(),
+            "datapc64_textures4.forge\nstreaminginstall.ini\nregex:^videos/4k \\(hd\\)/\n"
+        );
+    }
+}
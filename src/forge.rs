@@ -0,0 +1,470 @@
+use std::{
+    collections::BTreeMap,
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+// layout of the Anvil forge container as used by Siege:
+//   0x00  magic "scimitar\0"
+//   0x09  u32  version
+//   0x0D  u64  offset of the first entry table header
+// each entry table header is followed by a chain of further tables:
+//   0x00  u32  entry count
+//   0x18  u32  max entries
+//   0x20  u64  offset of the entries
+//   0x28  u64  offset of the next table header, or u64::MAX
+//   0x38  u64  offset of the metadata records
+// an entry is { u64 data offset, u64 uid, u32 data size } and every entry has a metadata record,
+// whose u32 at METADATA_TYPE_OFFSET is the entry type.
+// the table and metadata layout isn't taken from a published spec, the ignored `reads_real_forges`
+// test checks it against an actual install, run it with SHEARS_TEST_INSTALL set to one
+const FORGE_MAGIC: &[u8; 9] = b"scimitar\0";
+const HEADER_SIZE: u64 = 0x15;
+const TABLE_HEADER_SIZE: u64 = 0x48;
+const ENTRY_SIZE: u64 = 0x14;
+const METADATA_SIZE: u64 = 0x40;
+const METADATA_TYPE_OFFSET: u64 = 0x24;
+const NO_NEXT_TABLE: u64 = u64::MAX;
+const MAX_TABLES: usize = 4096; // guards against offset loops in corrupt files
+
+#[derive(Debug)]
+pub enum ForgeError {
+    Io(std::io::Error),
+    Empty,
+    InvalidMagic,
+    Truncated { needed: u64, file_len: u64 },
+    Malformed(&'static str),
+}
+
+impl std::fmt::Display for ForgeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "unable to read file: {e}"),
+            Self::Empty => write!(f, "file is empty"),
+            Self::InvalidMagic => write!(f, "invalid header, not a forge file"),
+            Self::Truncated { needed, file_len } => write!(
+                f,
+                "truncated, needs at least {needed} bytes but the file is {file_len} bytes"
+            ),
+            Self::Malformed(reason) => write!(f, "malformed, {reason}"),
+        }
+    }
+}
+
+impl From<std::io::Error> for ForgeError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ForgeEntry {
+    pub offset: u64,
+    pub size: u32,
+    pub entry_type: u32,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ForgeTypeSummary {
+    pub entry_type: u32,
+    pub count: usize,
+    pub size: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct ForgeFile {
+    pub version: u32,
+    pub file_len: u64,
+    pub entries: Vec<ForgeEntry>,
+}
+
+impl ForgeFile {
+    pub fn total_entry_size(&self) -> u64 {
+        self.entries.iter().map(|entry| u64::from(entry.size)).sum()
+    }
+
+    pub fn summarize_by_type(&self) -> Vec<ForgeTypeSummary> {
+        let mut summaries: BTreeMap<u32, ForgeTypeSummary> = BTreeMap::new();
+
+        for entry in &self.entries {
+            let summary = summaries
+                .entry(entry.entry_type)
+                .or_insert_with(|| ForgeTypeSummary {
+                    entry_type: entry.entry_type,
+                    ..ForgeTypeSummary::default()
+                });
+            summary.count += 1;
+            summary.size += u64::from(entry.size);
+        }
+
+        let mut summaries: Vec<_> = summaries.into_values().collect();
+        summaries.sort_by(|a, b| b.size.cmp(&a.size));
+        summaries
+    }
+}
+
+struct ForgeReader<R> {
+    reader: R,
+    file_len: u64,
+}
+
+impl<R: Read + Seek> ForgeReader<R> {
+    // makes sure `len` bytes starting at `offset` are inside the file before reading them
+    fn require(&self, offset: u64, len: u64) -> Result<(), ForgeError> {
+        let needed = offset
+            .checked_add(len)
+            .ok_or(ForgeError::Malformed("offset overflows"))?;
+
+        if needed > self.file_len {
+            return Err(ForgeError::Truncated {
+                needed,
+                file_len: self.file_len,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn read_vec(&mut self, offset: u64, len: u64) -> Result<Vec<u8>, ForgeError> {
+        self.require(offset, len)?;
+        self.reader.seek(SeekFrom::Start(offset))?;
+
+        let len = usize::try_from(len).map_err(|_e| ForgeError::Malformed("table too large"))?;
+        let mut buffer = vec![0; len];
+        self.reader.read_exact(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn read_table(
+        &mut self,
+        table_offset: u64,
+        entries: &mut Vec<ForgeEntry>,
+    ) -> Result<u64, ForgeError> {
+        let header = self.read_vec(table_offset, TABLE_HEADER_SIZE)?;
+
+        let entry_count = u64::from(le_u32(&header, 0x00)?);
+        let max_entries = u64::from(le_u32(&header, 0x18)?);
+        let entries_offset = le_u64(&header, 0x20)?;
+        let next_table_offset = le_u64(&header, 0x28)?;
+        let metadata_offset = le_u64(&header, 0x38)?;
+
+        if max_entries != 0 && entry_count > max_entries {
+            return Err(ForgeError::Malformed("entry count exceeds table capacity"));
+        }
+
+        let entry_data = self.read_vec(entries_offset, entry_count * ENTRY_SIZE)?;
+        let metadata = self.read_vec(metadata_offset, entry_count * METADATA_SIZE)?;
+
+        for index in 0..entry_count {
+            let entry_offset = index * ENTRY_SIZE;
+            let metadata_entry_offset = index * METADATA_SIZE;
+
+            let entry = ForgeEntry {
+                offset: le_u64(&entry_data, entry_offset)?,
+                size: le_u32(&entry_data, entry_offset + 0x10)?,
+                entry_type: le_u32(&metadata, metadata_entry_offset + METADATA_TYPE_OFFSET)?,
+            };
+
+            // the data of every entry has to be inside the file, otherwise the download was cut short
+            self.require(entry.offset, u64::from(entry.size))?;
+            entries.push(entry);
+        }
+
+        Ok(next_table_offset)
+    }
+}
+
+fn le_bytes<const N: usize>(buffer: &[u8], offset: u64) -> Result<[u8; N], ForgeError> {
+    usize::try_from(offset)
+        .ok()
+        .and_then(|start| buffer.get(start..start.checked_add(N)?))
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(ForgeError::Malformed("field outside of its table"))
+}
+
+fn le_u32(buffer: &[u8], offset: u64) -> Result<u32, ForgeError> {
+    le_bytes(buffer, offset).map(u32::from_le_bytes)
+}
+
+fn le_u64(buffer: &[u8], offset: u64) -> Result<u64, ForgeError> {
+    le_bytes(buffer, offset).map(u64::from_le_bytes)
+}
+
+pub fn read_forge(path: &Path) -> Result<ForgeFile, ForgeError> {
+    let file = std::fs::File::open(path)?;
+    let file_len = file.metadata()?.len();
+
+    if file_len == 0 {
+        return Err(ForgeError::Empty);
+    }
+
+    let mut reader = ForgeReader {
+        reader: BufReader::new(file),
+        file_len,
+    };
+
+    if file_len < HEADER_SIZE {
+        // a cut off header is still worth telling apart from a file that isn't a forge at all
+        let mut start = Vec::new();
+        reader.reader.read_to_end(&mut start)?;
+        if !FORGE_MAGIC.starts_with(&start) {
+            return Err(ForgeError::InvalidMagic);
+        }
+        reader.require(0, HEADER_SIZE)?;
+    }
+
+    let header = reader.read_vec(0, HEADER_SIZE)?;
+    if header.get(..FORGE_MAGIC.len()) != Some(FORGE_MAGIC.as_slice()) {
+        return Err(ForgeError::InvalidMagic);
+    }
+
+    let version = le_u32(&header, 0x09)?;
+    let mut table_offset = le_u64(&header, 0x0D)?;
+
+    let mut entries = Vec::new();
+    let mut table_count = 0;
+    while table_offset != NO_NEXT_TABLE {
+        table_count += 1;
+        if table_count > MAX_TABLES {
+            return Err(ForgeError::Malformed("too many entry tables"));
+        }
+
+        table_offset = reader.read_table(table_offset, &mut entries)?;
+    }
+
+    Ok(ForgeFile {
+        version,
+        file_len,
+        entries,
+    })
+}
+
+pub fn list_forge_files(folder: &Path) -> Vec<std::path::PathBuf> {
+    let Ok(entries) = std::fs::read_dir(folder) else {
+        return Vec::new();
+    };
+
+    let mut forges: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("forge"))
+        })
+        .collect();
+    forges.sort();
+    forges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERSION: u32 = 27;
+
+    // written out instead of reusing the reader's constants, the fixture follows the layout comment
+    // rather than the code under test
+    const FIXTURE_TABLE_HEADER_SIZE: u64 = 0x48;
+    const FIXTURE_ENTRY_SIZE: u64 = 0x14;
+    const FIXTURE_METADATA_SIZE: u64 = 0x40;
+    const FIXTURE_METADATA_TYPE_OFFSET: u64 = 0x24;
+
+    fn put_u32(data: &mut [u8], offset: u64, value: u32) {
+        let offset = usize::try_from(offset).expect("offset fits");
+        data.get_mut(offset..offset + 4)
+            .expect("put_u32: Out of bounds error")
+            .copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u64(data: &mut [u8], offset: u64, value: u64) {
+        let offset = usize::try_from(offset).expect("offset fits");
+        data.get_mut(offset..offset + 8)
+            .expect("put_u64: Out of bounds error")
+            .copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn len(data: &[u8]) -> u64 {
+        u64::try_from(data.len()).expect("length fits")
+    }
+
+    // a forge laid out as described at the top of forge.rs, one entry table per slice of entries.
+    // entries are (type, data)
+    fn forge_fixture(tables: &[&[(u32, &[u8])]]) -> Vec<u8> {
+        let mut data = vec![0_u8; 0x15];
+        data.get_mut(..FORGE_MAGIC.len())
+            .expect("forge_fixture: Out of bounds error")
+            .copy_from_slice(FORGE_MAGIC);
+        put_u32(&mut data, 0x09, VERSION);
+        put_u64(&mut data, 0x0D, u64::MAX);
+
+        let mut previous_next_offset = 0x0D;
+        for entries in tables {
+            let count = u32::try_from(entries.len()).expect("count fits");
+            let table_offset = len(&data);
+            put_u64(&mut data, previous_next_offset, table_offset);
+
+            let entries_offset = table_offset + FIXTURE_TABLE_HEADER_SIZE;
+            let metadata_offset = entries_offset + u64::from(count) * FIXTURE_ENTRY_SIZE;
+            let data_offset = metadata_offset + u64::from(count) * FIXTURE_METADATA_SIZE;
+            data.resize(usize::try_from(data_offset).expect("offset fits"), 0);
+
+            put_u32(&mut data, table_offset, count);
+            put_u32(&mut data, table_offset + 0x18, count);
+            put_u64(&mut data, table_offset + 0x20, entries_offset);
+            put_u64(&mut data, table_offset + 0x28, u64::MAX);
+            put_u64(&mut data, table_offset + 0x38, metadata_offset);
+            previous_next_offset = table_offset + 0x28;
+
+            for (index, &(entry_type, contents)) in (0_u64..).zip(entries.iter()) {
+                let offset = len(&data);
+                data.extend_from_slice(contents);
+
+                let entry = entries_offset + index * FIXTURE_ENTRY_SIZE;
+                put_u64(&mut data, entry, offset);
+                put_u64(&mut data, entry + 0x08, index + 1);
+                put_u32(
+                    &mut data,
+                    entry + 0x10,
+                    u32::try_from(contents.len()).expect("size fits"),
+                );
+                put_u32(
+                    &mut data,
+                    metadata_offset + index * FIXTURE_METADATA_SIZE + FIXTURE_METADATA_TYPE_OFFSET,
+                    entry_type,
+                );
+            }
+        }
+
+        data
+    }
+
+    fn read_fixture(data: &[u8]) -> Result<ForgeFile, ForgeError> {
+        let file = tempfile::Builder::new()
+            .suffix(".forge")
+            .tempfile()
+            .expect("Failed to create the fixture");
+        std::fs::write(file.path(), data).expect("Failed to write the fixture");
+        read_forge(file.path())
+    }
+
+    #[test]
+    fn reads_entries_across_tables() {
+        let data = forge_fixture(&[
+            &[(0x1, b"first"), (0x2, b"second entry")],
+            &[(0x1, b"third")],
+        ]);
+
+        let forge = read_fixture(&data).expect("The fixture should be valid");
+        assert_eq!(forge.version, VERSION);
+        assert_eq!(forge.file_len, len(&data));
+        assert_eq!(forge.entries.len(), 3);
+        assert_eq!(forge.total_entry_size(), 22);
+
+        let summaries = forge.summarize_by_type();
+        assert_eq!(
+            summaries
+                .iter()
+                .map(|summary| (summary.entry_type, summary.count, summary.size))
+                .collect::<Vec<_>>(),
+            vec![(0x2, 1, 12), (0x1, 2, 10)]
+        );
+    }
+
+    #[test]
+    fn reads_forges_without_entries() {
+        let forge = read_fixture(&forge_fixture(&[])).expect("A forge without tables is valid");
+        assert!(forge.entries.is_empty());
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let mut data = forge_fixture(&[&[(0x1, b"entry data")]]);
+        data.truncate(data.len() - 4);
+
+        assert!(matches!(
+            read_fixture(&data),
+            Err(ForgeError::Truncated { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_cut_off_headers() {
+        assert!(matches!(
+            read_fixture(b"scimi"),
+            Err(ForgeError::Truncated { .. })
+        ));
+        assert!(matches!(
+            read_fixture(b"abc"),
+            Err(ForgeError::InvalidMagic)
+        ));
+        assert!(matches!(read_fixture(b""), Err(ForgeError::Empty)));
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let mut data = forge_fixture(&[&[(0x1, b"entry")]]);
+        if let Some(first) = data.first_mut() {
+            *first = b'x';
+        }
+
+        assert!(matches!(read_fixture(&data), Err(ForgeError::InvalidMagic)));
+    }
+
+    #[test]
+    fn rejects_overfull_tables() {
+        let mut data = forge_fixture(&[&[(0x1, b"entry")]]);
+        let table_offset = 0x15;
+        put_u32(&mut data, table_offset + 0x18, 0x0); // no capacity limit is fine
+        assert!(read_fixture(&data).is_ok());
+
+        put_u32(&mut data, table_offset, 2);
+        put_u32(&mut data, table_offset + 0x18, 1);
+        assert!(matches!(read_fixture(&data), Err(ForgeError::Malformed(_))));
+    }
+
+    #[test]
+    fn rejects_table_loops() {
+        let mut data = forge_fixture(&[&[(0x1, b"entry")]]);
+        let table_offset = 0x15;
+        put_u64(&mut data, table_offset + 0x28, table_offset);
+
+        assert!(matches!(read_fixture(&data), Err(ForgeError::Malformed(_))));
+    }
+
+    // sanity checks that would fail if the layout constants were off
+    #[test]
+    #[ignore = "needs a Siege install, set SHEARS_TEST_INSTALL to its folder"]
+    fn reads_real_forges() {
+        let install = std::env::var_os("SHEARS_TEST_INSTALL").expect("SHEARS_TEST_INSTALL is set");
+        let forges = list_forge_files(Path::new(&install));
+        assert!(!forges.is_empty(), "no forges in the install");
+
+        for path in forges {
+            let forge = read_forge(&path)
+                .unwrap_or_else(|e| panic!("Failed to read {}: {e}", path.display()));
+
+            let mut entries = forge.entries.clone();
+            entries.sort_by_key(|entry| entry.offset);
+            for pair in entries.windows(2) {
+                if let [first, second] = pair {
+                    assert!(
+                        first.offset + u64::from(first.size) <= second.offset,
+                        "{}: entries overlap",
+                        path.display()
+                    );
+                }
+            }
+
+            // a wrong type offset reads arbitrary data, which has far more distinct values
+            let types = forge.summarize_by_type();
+            assert!(
+                types.len() <= 256,
+                "{}: {} entry types",
+                path.display(),
+                types.len()
+            );
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::path::Path;
+
+use crate::{
+    categories::{ShearCategory, find_category},
+    settings::get_config_dir,
+};
+
+const BUILT_IN_DEFINITIONS: [&str; 1] = [include_str!("../assets/games/siege.toml")];
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct GameFingerprint {
+    pub files: Vec<String>,
+}
+
+// where the game keeps its user settings, relative to Documents/My Games
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct GameSettingsDefinition {
+    pub folder: String,
+    pub file: String,
+    // the `[section]` holding the key, empty when the key comes before any section
+    #[serde(default)]
+    pub texture_quality_section: String,
+    pub texture_quality_key: String,
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct GameDefinition {
+    pub id: String,
+    pub name: String,
+    pub process_names: Vec<String>,
+    pub categories: Vec<String>,
+    pub fingerprint: GameFingerprint,
+    pub settings: Option<GameSettingsDefinition>,
+    // files Shears must never delete, fingerprint files are always protected too
+    #[serde(default)]
+    pub protected_files: Vec<String>,
+}
+
+impl GameDefinition {
+    fn built_in() -> Vec<Self> {
+        BUILT_IN_DEFINITIONS
+            .iter()
+            .map(|contents| {
+                toml::from_str(contents).expect("Failed to parse built-in game definition")
+            })
+            .collect()
+    }
+
+    // built-in definitions first, then the user's own ones from the `games` folder
+    // a user definition with the same id as a built-in one replaces it
+    pub fn load_all() -> Vec<Self> {
+        let mut definitions = Self::built_in();
+
+        let games_folder = get_config_dir().join("games");
+        let Ok(entries) = std::fs::read_dir(&games_folder) else {
+            return definitions;
+        };
+
+        let mut paths: Vec<_> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
+            })
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let definition: Self = match std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|contents| toml::from_str(&contents).map_err(|e| e.to_string()))
+            {
+                Ok(definition) => definition,
+                Err(e) => {
+                    log::error!("Failed to load game definition {}: {e}", path.display());
+                    continue;
+                }
+            };
+
+            log::info!(
+                "Loaded game definition `{}` from {}",
+                definition.id,
+                path.display()
+            );
+
+            if let Some(existing) = definitions.iter_mut().find(|d| d.id == definition.id) {
+                *existing = definition;
+            } else {
+                definitions.push(definition);
+            }
+        }
+
+        definitions
+    }
+
+    pub fn matches_file_names(&self, file_names: &[std::ffi::OsString]) -> bool {
+        !self.fingerprint.files.is_empty()
+            && self.fingerprint.files.iter().all(|required| {
+                file_names.iter().any(|name| {
+                    name.to_str()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(required))
+                })
+            })
+    }
+
+    pub fn matches_folder(&self, folder: &Path) -> bool {
+        let Ok(entries) = std::fs::read_dir(folder) else {
+            return false;
+        };
+
+        let file_names: Vec<_> = entries.flatten().map(|entry| entry.file_name()).collect();
+        self.matches_file_names(&file_names)
+    }
+
+    pub fn shear_categories(&self) -> Vec<&'static dyn ShearCategory> {
+        self.categories
+            .iter()
+            .filter_map(|id| {
+                let category = find_category(id);
+                if category.is_none() {
+                    log::warn!("Game definition `{}` uses unknown category `{id}`", self.id);
+                }
+                category
+            })
+            .collect()
+    }
+
+    pub fn is_running(&self, sys: &mut sysinfo::System) -> bool {
+        sys.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::All,
+            true,
+            sysinfo::ProcessRefreshKind::nothing(), // only retrieve basic info like name and PID
+        );
+
+        // checks all possible process names to see if the game is open
+        sys.processes().values().any(|process| {
+            self.process_names
+                .iter()
+                .any(|game_name| process.name().eq_ignore_ascii_case(game_name))
+        })
+    }
+}
+
+// picks the definition whose fingerprint matches the folder, defaulting to the first one (Siege)
+// so loosely selected folders keep working like they always did
+pub fn detect_game(definitions: &[GameDefinition], folder: &Path) -> GameDefinition {
+    definitions
+        .iter()
+        .find(|definition| definition.matches_folder(folder))
+        .or_else(|| definitions.first())
+        .cloned()
+        .unwrap_or_default()
+}
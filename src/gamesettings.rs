@@ -0,0 +1,247 @@
+use std::path::{Path, PathBuf};
+
+use crate::{games::GameSettingsDefinition, types::ForgeTextureQualityLevel};
+
+pub const GAME_SETTINGS_BACKUP_EXTENSION: &str = "shears-backup";
+
+#[derive(Clone, Debug)]
+pub struct GameSettingsFile {
+    pub path: PathBuf,
+    pub texture_quality: Option<ForgeTextureQualityLevel>,
+}
+
+// the game keeps one settings folder per account, each with its own settings file
+fn settings_files_in(documents: &Path, definition: &GameSettingsDefinition) -> Vec<PathBuf> {
+    let game_folder = documents.join("My Games").join(&definition.folder);
+
+    let mut files = vec![game_folder.join(&definition.file)];
+    if let Ok(entries) = std::fs::read_dir(&game_folder) {
+        files.extend(
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .map(|path| path.join(&definition.file)),
+        );
+    }
+
+    files.retain(|path| path.is_file());
+    files
+}
+
+// Documents folders of every user of the Wine prefix the install lives in, if any
+fn wine_documents_folders(install_folder: &Path) -> Vec<PathBuf> {
+    let mut prefixes: Vec<PathBuf> = install_folder
+        .ancestors()
+        .filter(|ancestor| ancestor.join("drive_c").is_dir())
+        .map(Path::to_path_buf)
+        .collect();
+
+    if let Some(prefix) = std::env::var_os("WINEPREFIX") {
+        prefixes.push(PathBuf::from(prefix));
+    }
+
+    let mut documents = Vec::new();
+    for prefix in prefixes {
+        let Ok(users) = std::fs::read_dir(prefix.join("drive_c").join("users")) else {
+            continue;
+        };
+
+        for user in users.flatten() {
+            documents.push(user.path().join("Documents"));
+            documents.push(user.path().join("My Documents"));
+        }
+    }
+
+    documents
+}
+
+// every line, with its value when it holds the texture quality key of the configured section
+fn texture_quality_lines<'a>(
+    contents: &'a str,
+    definition: &'a GameSettingsDefinition,
+) -> impl Iterator<Item = (&'a str, Option<&'a str>)> {
+    let mut section = "";
+    contents.lines().map(move |line| {
+        if let Some(header) = line
+            .trim()
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            section = header.trim();
+            return (line, None);
+        }
+
+        let value = line.split_once('=').and_then(|(key, value)| {
+            (section.eq_ignore_ascii_case(&definition.texture_quality_section)
+                && key
+                    .trim()
+                    .eq_ignore_ascii_case(&definition.texture_quality_key))
+            .then_some(value)
+        });
+        (line, value)
+    })
+}
+
+fn parse_texture_quality(
+    contents: &str,
+    definition: &GameSettingsDefinition,
+) -> Option<ForgeTextureQualityLevel> {
+    texture_quality_lines(contents, definition).find_map(|(_, value)| {
+        value?
+            .trim()
+            .parse()
+            .ok()
+            .and_then(ForgeTextureQualityLevel::convert_from_i32)
+    })
+}
+
+pub fn find_game_settings(
+    install_folder: &Path,
+    definition: &GameSettingsDefinition,
+) -> Vec<GameSettingsFile> {
+    let mut documents = wine_documents_folders(install_folder);
+    if let Some(user_dirs) = directories::UserDirs::new()
+        && let Some(document_dir) = user_dirs.document_dir()
+    {
+        documents.push(document_dir.to_path_buf());
+    }
+
+    let mut paths: Vec<PathBuf> = documents
+        .iter()
+        .flat_map(|documents| settings_files_in(documents, definition))
+        .collect();
+    paths.sort();
+    paths.dedup();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let texture_quality = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|contents| parse_texture_quality(&contents, definition));
+
+            log::info!(
+                "Found game settings {} (texture quality: {texture_quality:?})",
+                path.display()
+            );
+            GameSettingsFile {
+                path,
+                texture_quality,
+            }
+        })
+        .collect()
+}
+
+// rewrites the texture quality line of the configured section, keeping the rest of the file as is
+pub fn set_texture_quality(
+    settings: &GameSettingsFile,
+    definition: &GameSettingsDefinition,
+    level: ForgeTextureQualityLevel,
+) -> std::io::Result<()> {
+    let contents = std::fs::read_to_string(&settings.path)?;
+
+    let backup_path = settings.path.with_extension(format!(
+        "{}.{GAME_SETTINGS_BACKUP_EXTENSION}",
+        settings
+            .path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+    ));
+    // the first backup holds the settings from before Shears ever touched them, later ones would not
+    if !backup_path.exists() {
+        std::fs::copy(&settings.path, &backup_path)?;
+        log::info!("Backed up game settings to {}", backup_path.display());
+    }
+
+    let line_ending = if contents.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut output = texture_quality_lines(&contents, definition)
+        .map(|(line, value)| match (value, line.split_once('=')) {
+            (Some(_), Some((key, _))) => format!("{key}={}", level.convert_to_i32()),
+            _ => line.to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join(line_ending);
+    if contents.ends_with('\n') {
+        output.push_str(line_ending);
+    }
+
+    std::fs::write(&settings.path, output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS: &str = "[DISPLAY]\r\nTextureQuality=9\r\n\r\n[DISPLAY_SETTINGS]\r\nResolutionWidth=1920\r\nTextureQuality=4\r\n";
+
+    fn definition() -> GameSettingsDefinition {
+        GameSettingsDefinition {
+            folder: "Rainbow Six - Siege".to_owned(),
+            file: "GameSettings.ini".to_owned(),
+            texture_quality_section: "display_settings".to_owned(),
+            texture_quality_key: "TextureQuality".to_owned(),
+        }
+    }
+
+    fn settings_file(folder: &Path, contents: &str) -> GameSettingsFile {
+        let path = folder.join("GameSettings.ini");
+        std::fs::write(&path, contents).expect("Failed to write the test settings");
+        GameSettingsFile {
+            path,
+            texture_quality: parse_texture_quality(contents, &definition()),
+        }
+    }
+
+    #[test]
+    fn parses_the_key_of_the_configured_section() {
+        assert_eq!(
+            parse_texture_quality(SETTINGS, &definition()),
+            Some(ForgeTextureQualityLevel::Ultra)
+        );
+        assert_eq!(
+            parse_texture_quality("[DISPLAY]\nTextureQuality=2\n", &definition()),
+            None
+        );
+    }
+
+    #[test]
+    fn rewrites_only_the_configured_section_and_keeps_line_endings() {
+        let folder = tempfile::tempdir().expect("Failed to create the test folder");
+        let settings = settings_file(folder.path(), SETTINGS);
+
+        set_texture_quality(&settings, &definition(), ForgeTextureQualityLevel::Medium)
+            .expect("Failed to set the texture quality");
+
+        let contents =
+            std::fs::read_to_string(&settings.path).expect("Failed to read the test settings");
+        assert_eq!(
+            contents,
+            "[DISPLAY]\r\nTextureQuality=9\r\n\r\n[DISPLAY_SETTINGS]\r\nResolutionWidth=1920\r\nTextureQuality=1\r\n"
+        );
+    }
+
+    #[test]
+    fn keeps_the_first_backup() {
+        let folder = tempfile::tempdir().expect("Failed to create the test folder");
+        let settings = settings_file(folder.path(), SETTINGS);
+        let backup_path = folder
+            .path()
+            .join(format!("GameSettings.ini.{GAME_SETTINGS_BACKUP_EXTENSION}"));
+
+        set_texture_quality(&settings, &definition(), ForgeTextureQualityLevel::High)
+            .expect("Failed to set the texture quality");
+        set_texture_quality(&settings, &definition(), ForgeTextureQualityLevel::Low)
+            .expect("Failed to set the texture quality");
+
+        assert_eq!(
+            std::fs::read_to_string(backup_path).expect("Failed to read the backup"),
+            SETTINGS
+        );
+    }
+}
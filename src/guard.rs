@@ -0,0 +1,309 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    diskusage::DiskUsage, games::GameDefinition, progress::JobProgress,
+    streaminginstall::STREAMING_INSTALL_FILE_NAME,
+};
+
+// never removed no matter what a category plans, on top of what the game definition protects
+const ALWAYS_PROTECTED: [&str; 2] = ["datapc64.forge", STREAMING_INSTALL_FILE_NAME];
+
+#[derive(Debug)]
+pub enum GuardError {
+    Io(PathBuf, std::io::Error),
+    OutsideRoot(PathBuf),
+    IsRoot(PathBuf),
+    Protected(PathBuf),
+    Symlink(PathBuf),
+}
+
+impl std::fmt::Display for GuardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(path, e) => write!(f, "{}: {e}", path.display()),
+            Self::OutsideRoot(path) => write!(
+                f,
+                "{} is outside of the installation folder",
+                path.display()
+            ),
+            Self::IsRoot(path) => write!(f, "{} is the installation folder itself", path.display()),
+            Self::Protected(path) => write!(f, "{} is a protected file", path.display()),
+            Self::Symlink(path) => write!(f, "{} is a symbolic link", path.display()),
+        }
+    }
+}
+
+// every deletion goes through here, so no category can ever remove something it shouldn't
+#[derive(Debug)]
+pub struct DeletionGuard {
+    root: PathBuf,
+    protected: Vec<String>,
+}
+
+impl DeletionGuard {
+    pub fn new(root: &Path, game: &GameDefinition) -> Result<Self, GuardError> {
+        let root = root
+            .canonicalize()
+            .map_err(|e| GuardError::Io(root.to_path_buf(), e))?;
+
+        let protected = ALWAYS_PROTECTED
+            .iter()
+            .map(|&name| name.to_owned())
+            .chain(game.fingerprint.files.iter().cloned())
+            .chain(game.protected_files.iter().cloned())
+            .map(|name| name.to_ascii_lowercase())
+            .collect();
+
+        Ok(Self { root, protected })
+    }
+
+    fn is_protected(&self, path: &Path) -> bool {
+        path.file_name()
+            .and_then(|s| s.to_str())
+            .is_some_and(|name| self.protected.contains(&name.to_ascii_lowercase()))
+    }
+
+    // checks a single path without following symlinks, returns its canonical form
+    fn check_entry(&self, path: &Path) -> Result<PathBuf, GuardError> {
+        let metadata =
+            std::fs::symlink_metadata(path).map_err(|e| GuardError::Io(path.to_path_buf(), e))?;
+        if metadata.file_type().is_symlink() {
+            return Err(GuardError::Symlink(path.to_path_buf()));
+        }
+
+        let canonical = path
+            .canonicalize()
+            .map_err(|e| GuardError::Io(path.to_path_buf(), e))?;
+        if canonical == self.root {
+            return Err(GuardError::IsRoot(path.to_path_buf()));
+        }
+        if !canonical.starts_with(&self.root) {
+            return Err(GuardError::OutsideRoot(path.to_path_buf()));
+        }
+        if self.is_protected(&canonical) {
+            return Err(GuardError::Protected(path.to_path_buf()));
+        }
+
+        Ok(canonical)
+    }
+
+    // folders are checked entry by entry, a symlink or protected file anywhere inside refuses the whole folder
+    pub fn check(&self, path: &Path) -> Result<PathBuf, GuardError> {
+        let canonical = self.check_entry(path)?;
+
+        if canonical.is_dir() {
+            let entries =
+                std::fs::read_dir(&canonical).map_err(|e| GuardError::Io(canonical.clone(), e))?;
+            for entry in entries {
+                let entry = entry.map_err(|e| GuardError::Io(canonical.clone(), e))?;
+                self.check(&entry.path())?;
+            }
+        }
+
+        Ok(canonical)
+    }
+
+    // staging a removal counts as deleting it, so it is checked just the same
+    pub fn move_into(&self, path: &Path, destination: &Path) -> Result<(), GuardError> {
+        let canonical = self.check(path).inspect_err(|e| {
+            log::error!("Refusing to delete: {e}");
+        })?;
+
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| GuardError::Io(parent.to_path_buf(), e))?;
+        }
+
+        std::fs::rename(&canonical, destination).map_err(|e| GuardError::Io(path.to_path_buf(), e))
+    }
+
+    // files are removed one at a time so a cancelled job stops between files,
+    // the caller checks `progress.is_cancelled()` to know whether everything is gone
+    pub fn remove(&self, path: &Path, progress: &JobProgress) -> Result<(), GuardError> {
+        let canonical = self.check(path).inspect_err(|e| {
+            log::error!("Refusing to delete: {e}");
+        })?;
+
+        remove_tracked(&canonical, progress)
+    }
+}
+
+fn remove_tracked(path: &Path, progress: &JobProgress) -> Result<(), GuardError> {
+    if progress.is_cancelled() {
+        return Ok(());
+    }
+
+    if !path.is_dir() {
+        progress.set_current(path.display().to_string());
+        // what the drive gets back, hard linked files free nothing
+        let size = std::fs::metadata(path)
+            .map(|metadata| DiskUsage::of_file(&metadata).reclaimable)
+            .unwrap_or(0);
+        std::fs::remove_file(path).map_err(|e| GuardError::Io(path.to_path_buf(), e))?;
+        progress.add_file(size);
+        return Ok(());
+    }
+
+    let entries = std::fs::read_dir(path).map_err(|e| GuardError::Io(path.to_path_buf(), e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| GuardError::Io(path.to_path_buf(), e))?;
+        remove_tracked(&entry.path(), progress)?;
+    }
+
+    if progress.is_cancelled() {
+        return Ok(());
+    }
+
+    std::fs::remove_dir(path).map_err(|e| GuardError::Io(path.to_path_buf(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::GameFingerprint;
+
+    // an install with a videos folder, a settings folder holding a protected file and the files
+    // every install has at the root
+    fn test_install() -> tempfile::TempDir {
+        let install = tempfile::tempdir().expect("Failed to create the test install");
+        let root = install.path();
+        for folder in ["videos", "settings"] {
+            std::fs::create_dir_all(root.join(folder)).expect("Failed to create the test install");
+        }
+        for file in [
+            "videos/intro.bik",
+            "settings/BattlEye.dll",
+            "DataPC64.forge",
+            "StreamingInstall.ini",
+            "RainbowSix.exe",
+            "datapc64_textures4.forge",
+        ] {
+            std::fs::write(root.join(file), "data").expect("Failed to create the test install");
+        }
+        install
+    }
+
+    fn guard(root: &Path) -> DeletionGuard {
+        let game = GameDefinition {
+            fingerprint: GameFingerprint {
+                files: vec!["RainbowSix.exe".to_owned()],
+            },
+            protected_files: vec!["battleye.dll".to_owned()],
+            ..Default::default()
+        };
+        DeletionGuard::new(root, &game).expect("Failed to make a guard")
+    }
+
+    #[test]
+    fn allows_regular_files_and_folders() {
+        let install = test_install();
+        let guard = guard(install.path());
+
+        assert!(guard.check(&install.path().join("videos")).is_ok());
+        assert!(
+            guard
+                .check(&install.path().join("datapc64_textures4.forge"))
+                .is_ok()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_symlinks() {
+        let install = test_install();
+        let root = install.path();
+        let outside = tempfile::tempdir().expect("Failed to create the outside folder");
+        std::os::unix::fs::symlink(outside.path(), root.join("linked"))
+            .expect("Failed to create a symlink");
+        std::os::unix::fs::symlink(outside.path(), root.join("videos").join("linked"))
+            .expect("Failed to create a symlink");
+
+        let guard = guard(root);
+        assert!(matches!(
+            guard.check(&root.join("linked")),
+            Err(GuardError::Symlink(_))
+        ));
+        assert!(matches!(
+            guard.check(&root.join("videos")),
+            Err(GuardError::Symlink(_))
+        ));
+    }
+
+    #[test]
+    fn refuses_paths_outside_the_install() {
+        let install = test_install();
+        let outside = tempfile::tempdir().expect("Failed to create the outside folder");
+        let guard = guard(install.path());
+
+        assert!(matches!(
+            guard.check(outside.path()),
+            Err(GuardError::OutsideRoot(_))
+        ));
+        let escaping = install.path().join("videos").join("..").join("..").join(
+            outside
+                .path()
+                .file_name()
+                .expect("temp folders have a name"),
+        );
+        assert!(matches!(
+            guard.check(&escaping),
+            Err(GuardError::OutsideRoot(_))
+        ));
+    }
+
+    #[test]
+    fn refuses_the_install_itself() {
+        let install = test_install();
+        let guard = guard(install.path());
+
+        assert!(matches!(
+            guard.check(install.path()),
+            Err(GuardError::IsRoot(_))
+        ));
+        assert!(matches!(
+            guard.check(&install.path().join("videos").join("..")),
+            Err(GuardError::IsRoot(_))
+        ));
+    }
+
+    #[test]
+    fn refuses_protected_files_in_any_case() {
+        let install = test_install();
+        let root = install.path();
+        let guard = guard(root);
+
+        for file in ["DataPC64.forge", "StreamingInstall.ini", "RainbowSix.exe"] {
+            assert!(
+                matches!(guard.check(&root.join(file)), Err(GuardError::Protected(_))),
+                "{file} should be protected"
+            );
+        }
+        assert!(
+            matches!(
+                guard.check(&root.join("settings")),
+                Err(GuardError::Protected(_))
+            ),
+            "a folder holding a protected file can't be removed"
+        );
+    }
+
+    #[test]
+    fn remove_stops_when_cancelled() {
+        let install = test_install();
+        let guard = guard(install.path());
+        let progress = JobProgress::default();
+        progress.cancel();
+
+        guard
+            .remove(&install.path().join("videos"), &progress)
+            .expect("A cancelled removal isn't an error");
+        assert!(install.path().join("videos").join("intro.bik").is_file());
+        assert_eq!(progress.files(), 0);
+
+        let progress = JobProgress::default();
+        guard
+            .remove(&install.path().join("videos"), &progress)
+            .expect("Failed to remove the videos");
+        assert!(!install.path().join("videos").exists());
+        assert_eq!(progress.files(), 1);
+    }
+}
@@ -1,13 +1,20 @@
-use crate::types::{ForgeTextureQualityLevel, ShearingFeaturesAvailability};
-
-use std::io::{Result, Write as _};
-use std::path::Path;
-
-fn get_file_size(path: &std::path::Path) -> Result<u64> {
+use crate::{
+    categories::{CategoryAvailability, ShearCategory},
+    forge::list_forge_files,
+    progress::JobProgress,
+    sizecache::SizeCache,
+    types::ShearingFeaturesAvailability,
+};
+
+use std::io::Result;
+use std::path::{Path, PathBuf};
+
+pub fn get_file_size(path: &std::path::Path) -> Result<u64> {
     std::fs::metadata(path).map(|m| m.len())
 }
 
-fn get_folder_size(path: &std::path::Path) -> Result<u64> {
+// unreadable entries are skipped so one bad file doesn't hide the size of everything else
+pub fn get_folder_size(path: &std::path::Path) -> Result<u64> {
     let entries = std::fs::read_dir(path)?;
 
     let mut total_size: u64 = 0;
@@ -16,256 +23,151 @@ fn get_folder_size(path: &std::path::Path) -> Result<u64> {
 
         // handle subfolders (mostly for newer versions of siege)
         if path.is_dir() {
-            total_size += get_folder_size(&path)?;
+            match get_folder_size(&path) {
+                Ok(size) => total_size += size,
+                Err(e) => log::warn!("Unable to measure {}: {e}", path.display()),
+            }
             continue;
         }
 
-        let metadata = std::fs::metadata(&path)?;
-        total_size += metadata.len();
+        match std::fs::metadata(&path) {
+            Ok(metadata) => total_size += metadata.len(),
+            Err(e) => log::warn!("Unable to measure {}: {e}", path.display()),
+        }
     }
 
     Ok(total_size)
 }
 
-fn get_videos_subfolder_size(folder: &Path) -> Result<u64> {
-    let video_sub_folder = folder.join("videos");
-
-    if !video_sub_folder.exists() || !video_sub_folder.is_dir() {
-        return Ok(0);
-    }
-
-    get_folder_size(&video_sub_folder)
-}
-
-fn get_events_size(folder: &Path) -> u64 {
-    let Ok(entries) = std::fs::read_dir(folder) else {
-        return 0;
+pub fn get_path_size(path: &Path) -> u64 {
+    let size = if path.is_dir() {
+        get_folder_size(path)
+    } else {
+        get_file_size(path)
     };
 
-    let mut size = 0;
-    for entry in entries.flatten() {
-        let path = entry.path();
-
-        let Some(ext) = path.extension().and_then(|s| s.to_str()) else {
-            continue;
-        };
-
-        let Some(filename) = path.file_stem().and_then(|s| s.to_str()) else {
-            continue;
-        };
-
-        if !ext.eq_ignore_ascii_case("forge") && !ext.eq_ignore_ascii_case("depgraphbin") {
-            continue;
-        }
+    size.unwrap_or(0)
+}
 
-        if !filename.contains("events") {
-            continue;
-        }
+// checks the header and the declared entry table of every forge against the real file size,
+// broken downloads otherwise look like healthy installs
+fn find_damaged_forges(
+    forges: &[std::path::PathBuf],
+    cache: &SizeCache,
+    progress: &JobProgress,
+) -> Vec<(std::path::PathBuf, String)> {
+    forges
+        .iter()
+        .take_while(|_| !progress.is_cancelled())
+        .filter_map(|path| {
+            progress.set_current(path.display().to_string());
+
+            let reason = cache.forge_error(path)?;
+            log::warn!("Damaged forge {}: {reason}", path.display());
+            Some((path.clone(), reason))
+        })
+        .collect()
+}
 
-        size += get_file_size(&path).unwrap_or(0);
+pub fn get_shearing_features_availability(
+    folder: &Path,
+    categories: &[&'static dyn ShearCategory],
+    cache: &SizeCache,
+    progress: &JobProgress,
+) -> ShearingFeaturesAvailability {
+    let forges = list_forge_files(folder);
+
+    progress.set_current(format!("Measuring {}", folder.display()));
+    let install_size = cache.path_usage(folder, &mut Vec::new());
+
+    ShearingFeaturesAvailability {
+        has_forge_files: !forges.is_empty(),
+        install_size,
+        damaged_forges: find_damaged_forges(&forges, cache, progress),
+        categories: categories
+            .iter()
+            .take_while(|_| !progress.is_cancelled())
+            .map(|&category| {
+                progress.set_current(format!("Measuring {}", category.name()));
+                CategoryAvailability::analyze(category, folder, cache)
+            })
+            .collect(),
     }
-
-    size
 }
 
-pub fn get_shearing_features_availability(folder: &Path) -> ShearingFeaturesAvailability {
-    let mut features = ShearingFeaturesAvailability::default();
-
-    let Ok(entries) = std::fs::read_dir(folder) else {
-        return features;
+// `canonicalize` gives verbatim `\\?\C:\...` paths on Windows, while sysinfo reports mount points
+// as plain `C:\`, which a verbatim path never starts with
+fn strip_verbatim_prefix(path: &Path) -> PathBuf {
+    let Some(text) = path.to_str() else {
+        return path.to_path_buf();
     };
 
-    let mut has_forges = false;
-    let mut texture_sizes: [u64; ForgeTextureQualityLevel::COUNT] =
-        [0; ForgeTextureQualityLevel::COUNT];
-
-    for entry in entries.flatten() {
-        let path = entry.path();
-
-        let Some(ext) = path.extension().and_then(|s| s.to_str()) else {
-            continue;
-        };
-
-        let Some(filename) = path.file_stem().and_then(|s| s.to_str()) else {
-            continue;
-        };
-
-        if !ext.eq_ignore_ascii_case("forge") {
-            continue;
-        }
-
-        has_forges = true;
-
-        let Some((_, suffix)) = filename.split_once("textures") else {
-            continue;
-        };
-
-        let Some(first_char) = suffix.chars().next() else {
-            continue;
-        };
-
-        let Some(digit) = first_char.to_digit(10) else {
-            continue;
-        };
-
-        let level = digit as i32;
-        if !(0..=4).contains(&level) {
-            continue;
-        }
-
-        let Some(quality_level) = ForgeTextureQualityLevel::convert_from_i32(level) else {
-            continue;
-        };
-
-        *texture_sizes
-            .get_mut(quality_level as usize)
-            .expect("get_shearing_features_availability.*texture_sizes: Out of bounds error") +=
-            get_file_size(&path).unwrap_or(0);
+    if let Some(share) = text.strip_prefix(r"\\?\UNC\") {
+        return PathBuf::from(format!(r"\\{share}"));
     }
-
-    features.has_forge_files = has_forges;
-
-    let low = ForgeTextureQualityLevel::Low as usize;
-    for (dst, &size) in features
-        .textures
-        .iter_mut()
-        .skip(low)
-        .take(ForgeTextureQualityLevel::COUNT)
-        .zip(texture_sizes.iter().skip(low))
-    {
-        *dst = (size > 0, size);
+    match text.strip_prefix(r"\\?\") {
+        Some(rest) if rest.get(1..2) == Some(":") => PathBuf::from(rest),
+        _ => path.to_path_buf(),
     }
-
-    features.videos = {
-        match get_videos_subfolder_size(folder) {
-            Err(_) => (false, 0),
-            Ok(o) => (o > 0, o),
-        }
-    };
-
-    features.events = {
-        let res = get_events_size(folder);
-        (res > 0, res)
-    };
-
-    features
 }
 
-pub fn delete_texture_files(folder: &Path, min_quality_level: &ForgeTextureQualityLevel) {
-    // unless they add a texture quality level above ultra in the future, early exit to avoid useless code
-    if *min_quality_level == ForgeTextureQualityLevel::Ultra {
-        return;
-    }
-
-    let Ok(entries) = std::fs::read_dir(folder) else {
-        return;
-    };
-
-    for entry in entries.flatten() {
-        let path = entry.path();
-
-        let Some(ext) = path.extension().and_then(|s| s.to_str()) else {
-            continue;
-        };
-        if !ext.eq_ignore_ascii_case("forge") {
-            continue;
-        }
-
-        let Some(filename) = path.file_stem().and_then(|s| s.to_str()) else {
-            continue;
-        };
-        let Some((_, suffix)) = filename.split_once("textures") else {
-            continue;
-        };
-        let Some(level) = suffix.chars().next().and_then(|c| c.to_digit(10)) else {
-            continue;
-        };
-        let Some(quality) = ForgeTextureQualityLevel::convert_from_i32(level as i32) else {
-            continue;
-        };
-
-        if quality > *min_quality_level
-            && let Some(err) = std::fs::remove_file(&path).err()
-        {
-            let path_string = path.display().to_string();
-            log::warn!("Unable to delete {path_string} because {err}.");
-        }
-    }
+// nested mount points win over the ones they are mounted inside
+fn longest_mount_point<'a>(
+    mount_points: impl IntoIterator<Item = &'a Path>,
+    path: &Path,
+) -> Option<&'a Path> {
+    let path = strip_verbatim_prefix(path);
+
+    mount_points
+        .into_iter()
+        .filter(|mount_point| path.starts_with(strip_verbatim_prefix(mount_point)))
+        .max_by_key(|mount_point| mount_point.components().count())
 }
 
-pub fn delete_videos_folder(folder: &Path) {
-    let video_sub_folder = folder.join("videos");
+// the drive a path lives on
+fn find_disk<'a>(disks: &'a sysinfo::Disks, path: &Path) -> Option<&'a sysinfo::Disk> {
+    let path = path.canonicalize().ok()?;
+    let mount_point = longest_mount_point(disks.iter().map(sysinfo::Disk::mount_point), &path)?;
 
-    if let Some(err) = std::fs::remove_dir_all(&video_sub_folder).err() {
-        let path_string = video_sub_folder.display().to_string();
-        log::warn!("Unable to delete {path_string} because {err}.");
-    }
+    disks.iter().find(|disk| disk.mount_point() == mount_point)
 }
 
-pub fn delete_events_folder(folder: &Path) {
-    let Ok(entries) = std::fs::read_dir(folder) else {
-        return;
-    };
-
-    for entry in entries.flatten() {
-        let path = entry.path();
-
-        let Some(ext) = path.extension().and_then(|s| s.to_str()) else {
-            continue;
-        };
-        if !ext.eq_ignore_ascii_case("forge") && !ext.eq_ignore_ascii_case("depgraphbin") {
-            continue;
-        }
-
-        let Some(filename) = path.file_stem().and_then(|s| s.to_str()) else {
-            continue;
-        };
-
-        if !filename.contains("events") {
-            continue;
-        }
-
-        if let Some(err) = std::fs::remove_file(&path).err() {
-            let path_string = path.display().to_string();
-            log::warn!("Unable to delete event file {path_string} because {err}.");
-        }
-    }
+pub fn get_available_space(path: &Path) -> Option<u64> {
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    find_disk(&disks, path).map(sysinfo::Disk::available_space)
 }
 
-pub fn write_streaminginstall(siege_folder: &std::path::Path) -> std::io::Result<()> {
-    let streaming_install_path = siege_folder.join("streaminginstall.ini");
-
-    let mut file = std::fs::OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(streaming_install_path)?;
-
-    file.write_all(b"[MissionToChunk]\n[FileToChunk]\n")?;
-    file.flush()?;
-    Ok(())
+pub fn get_mount_point(path: &Path) -> Option<PathBuf> {
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    find_disk(&disks, path).map(|disk| disk.mount_point().to_path_buf())
 }
 
-pub fn is_siege_running(sys: &mut sysinfo::System) -> bool {
-    let possible_process_names = [
-        "rainbowsixgame.exe",
-        "rainbowsix.exe",
-        "rainbowsix_vulkan.exe",
-        "rainbowsix_dx11.exe",
-    ];
-
-    sys.refresh_processes_specifics(
-        sysinfo::ProcessesToUpdate::All,
-        true,
-        sysinfo::ProcessRefreshKind::nothing(), // only retrieve basic info like name and PID
-    );
-
-    // checks all possible siege process names to see if it's open
-    sys.processes().values().any(|process| {
-        possible_process_names
-            .iter()
-            .any(|&siege_name| process.name().eq_ignore_ascii_case(siege_name))
-    })
+// seconds since the unix epoch as `YYYY-MM-DD HH:MM UTC`, avoids pulling in a date crate
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds_of_day = timestamp % 86_400;
+
+    // civil date from days since 1970-01-01, http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02} UTC",
+        seconds_of_day / 3600,
+        (seconds_of_day % 3600) / 60
+    )
 }
 
 #[derive(Debug, PartialEq)]
@@ -380,3 +282,53 @@ pub fn run_shears_version_background_check(invoked_automatically: bool) {
             .expect("Failed to show dialog");
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_verbatim_prefixes() {
+        assert_eq!(
+            strip_verbatim_prefix(Path::new(r"\\?\C:\Games\Siege")),
+            PathBuf::from(r"C:\Games\Siege")
+        );
+        assert_eq!(
+            strip_verbatim_prefix(Path::new(r"\\?\UNC\server\games\Siege")),
+            PathBuf::from(r"\\server\games\Siege")
+        );
+        assert_eq!(
+            strip_verbatim_prefix(Path::new(r"\\?\Volume{1234}\Siege")),
+            PathBuf::from(r"\\?\Volume{1234}\Siege"),
+            "volume paths have no drive letter to fall back to"
+        );
+        assert_eq!(
+            strip_verbatim_prefix(Path::new("/mnt/games")),
+            PathBuf::from("/mnt/games")
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn matches_verbatim_paths_to_drives() {
+        let mount_points = [Path::new(r"C:\"), Path::new(r"D:\")];
+        assert_eq!(
+            longest_mount_point(mount_points, Path::new(r"\\?\D:\Games\Siege")),
+            Some(Path::new(r"D:\"))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn prefers_nested_mount_points() {
+        let mount_points = [Path::new("/"), Path::new("/mnt/games")];
+        assert_eq!(
+            longest_mount_point(mount_points, Path::new("/mnt/games/Siege")),
+            Some(Path::new("/mnt/games"))
+        );
+        assert_eq!(
+            longest_mount_point(mount_points, Path::new("/home/siege")),
+            Some(Path::new("/"))
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    categories::ShearSelection, games::GameDefinition, journal::unix_now, settings::get_config_dir,
+};
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct InstallRecord {
+    pub path: PathBuf,
+    pub label: String,
+    pub game: String,
+    pub season: Option<String>,
+    pub size: Option<u64>,
+    pub last_opened: Option<u64>,
+    // the shear history isn't kept here, it's read from the ledger inside the install
+    // what was removed the last time this install was sheared, items that showed up since are kept
+    pub removed: ShearSelection,
+}
+
+impl InstallRecord {
+    pub fn display_name(&self) -> String {
+        if !self.label.is_empty() {
+            return self.label.clone();
+        }
+
+        self.season.clone().unwrap_or_else(|| {
+            self.path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| self.path.display().to_string())
+        })
+    }
+}
+
+// finds a season like `Y5S3` in any folder name of the path, the way most people name their installs
+pub fn detect_season(path: &Path) -> Option<String> {
+    path.components().rev().find_map(|component| {
+        let name = component.as_os_str().to_string_lossy().to_ascii_uppercase();
+        let bytes = name.as_bytes();
+
+        (0..bytes.len()).find_map(|start| {
+            let rest = bytes.get(start..)?;
+            let year_digits = rest
+                .get(1..)?
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .count();
+            let season_digits = rest
+                .get(2 + year_digits..)?
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .count();
+
+            let is_season = rest.first() == Some(&b'Y')
+                && year_digits > 0
+                && rest.get(1 + year_digits) == Some(&b'S')
+                && season_digits > 0;
+
+            is_season
+                .then(|| name.get(start..start + 2 + year_digits + season_digits))
+                .flatten()
+                .map(str::to_owned)
+        })
+    })
+}
+
+// every folder that was opened or found by a scan, so they don't have to be picked again every session
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct InstallRegistry {
+    #[serde(default)]
+    installs: Vec<InstallRecord>,
+}
+
+impl InstallRegistry {
+    fn get_path() -> PathBuf {
+        get_config_dir().join("Installs.toml")
+    }
+
+    pub fn load_or_default() -> Self {
+        std::fs::read_to_string(Self::get_path())
+            .ok()
+            .and_then(|contents| {
+                toml::from_str(&contents)
+                    .inspect_err(|e| log::error!("Failed to parse the install registry: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn save_to_file(&self) {
+        let path = Self::get_path();
+        if let Some(parent) = path.parent()
+            && let Err(e) = std::fs::create_dir_all(parent)
+        {
+            log::error!("Failed to create folder: {e}");
+        }
+
+        match toml::to_string_pretty(self) {
+            Ok(contents) => {
+                if let Err(e) = std::fs::write(path, contents) {
+                    log::error!("Failed to save the install registry: {e}");
+                }
+            }
+            Err(e) => log::error!("Failed to serialize the install registry: {e}"),
+        }
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&InstallRecord> {
+        self.installs.iter().find(|install| install.path == path)
+    }
+
+    pub fn get_mut(&mut self, path: &Path) -> Option<&mut InstallRecord> {
+        self.installs
+            .iter_mut()
+            .find(|install| install.path == path)
+    }
+
+    fn entry(&mut self, path: &Path, game: &GameDefinition) -> &mut InstallRecord {
+        let index = if let Some(index) = self
+            .installs
+            .iter()
+            .position(|install| install.path == path)
+        {
+            index
+        } else {
+            self.installs.push(InstallRecord {
+                path: path.to_path_buf(),
+                season: detect_season(path),
+                ..InstallRecord::default()
+            });
+            self.installs.len() - 1
+        };
+
+        let install = self
+            .installs
+            .get_mut(index)
+            .expect("InstallRegistry.entry: Out of bounds error");
+        install.game.clone_from(&game.id);
+        install
+    }
+
+    pub fn add_found(&mut self, path: &Path, game: &GameDefinition) {
+        self.entry(path, game);
+    }
+
+    pub fn remember_opened(&mut self, path: &Path, game: &GameDefinition) {
+        self.entry(path, game).last_opened = Some(unix_now());
+    }
+
+    pub fn forget(&mut self, path: &Path) {
+        self.installs.retain(|install| install.path != path);
+    }
+
+    // most recently opened first, installs that were only found by a scan come last
+    pub fn sorted(&self) -> Vec<&InstallRecord> {
+        let mut installs: Vec<&InstallRecord> = self.installs.iter().collect();
+        installs.sort_by(|a, b| {
+            b.last_opened
+                .cmp(&a.last_opened)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        installs
+    }
+}
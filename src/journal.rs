@@ -0,0 +1,632 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    categories::{ShearPlan, find_category},
+    diskusage::path_usage,
+    games::GameDefinition,
+    guard::{DeletionGuard, GuardError},
+    helpers::get_path_size,
+    ledger::{LedgerEntry, LedgerFile, ShearLedger},
+    progress::JobProgress,
+    streaminginstall::{STREAMING_INSTALL_FILE_NAME, update_streaminginstall},
+};
+
+// everything Shears keeps inside an install lives in this folder
+pub const SHEARS_FOLDER_NAME: &str = ".shears";
+const JOURNAL_FILE_NAME: &str = "journal";
+const STAGING_FOLDER_NAME: &str = "staging";
+const STREAMING_INSTALL_SNAPSHOT_FILE_NAME: &str = "streaminginstall.ini.before";
+
+// a shear first moves every planned path into the staging folder and rewrites streaminginstall.ini,
+// all of which can still be undone. only then are the staged files deleted
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum JournalPhase {
+    Staging,
+    Committing,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct JournalEntry {
+    pub category: String,
+    pub path: PathBuf, // relative to the install
+    pub staged: bool,
+    #[serde(default)]
+    pub size: u64, // measured before staging, the ledger keeps it for re-downloads
+    #[serde(default)]
+    pub folder: bool,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct Journal {
+    pub started: u64,
+    pub phase: JournalPhase,
+    pub streaming_install_updated: bool,
+    pub entries: Vec<JournalEntry>,
+    #[serde(default)]
+    pub profile: Option<String>, // the profile the selection came from, for the ledger
+    #[serde(default)]
+    pub freed: u64, // reclaimable bytes deleted so far, a resumed shear adds to it
+
+    #[serde(skip)]
+    root: PathBuf,
+}
+
+#[derive(Debug, Default)]
+pub struct JournalOutcome {
+    pub removed: Vec<PathBuf>,
+    pub failures: Vec<String>, // paths that couldn't be staged, nothing was deleted
+    pub cancelled: bool, // the journal is left behind so the shear can be resumed or rolled back
+}
+
+pub fn shears_folder(root: &Path) -> PathBuf {
+    root.join(SHEARS_FOLDER_NAME)
+}
+
+// returns whether the folder is gone, folders that still hold a file are kept with it
+fn remove_empty_folders(folder: &Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(folder) else {
+        return false;
+    };
+
+    let mut is_empty = true;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() || !remove_empty_folders(&path) {
+            is_empty = false;
+        }
+    }
+
+    if !is_empty {
+        log::warn!("Leaving {} in place, it isn't empty.", folder.display());
+        return false;
+    }
+
+    std::fs::remove_dir(folder)
+        .inspect_err(|e| log::warn!("Unable to remove {}: {e}", folder.display()))
+        .is_ok()
+}
+
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+impl Journal {
+    fn path(root: &Path) -> PathBuf {
+        shears_folder(root).join(JOURNAL_FILE_NAME)
+    }
+
+    fn staging_folder(&self) -> PathBuf {
+        shears_folder(&self.root).join(STAGING_FOLDER_NAME)
+    }
+
+    fn snapshot_path(&self) -> PathBuf {
+        shears_folder(&self.root).join(STREAMING_INSTALL_SNAPSHOT_FILE_NAME)
+    }
+
+    pub fn exists(root: &Path) -> bool {
+        Self::path(root).is_file()
+    }
+
+    pub fn load(root: &Path) -> Option<Self> {
+        let contents = std::fs::read_to_string(Self::path(root)).ok()?;
+
+        match toml::from_str::<Self>(&contents) {
+            Ok(journal) => Some(Self {
+                root: root.to_path_buf(),
+                ..journal
+            }),
+            Err(e) => {
+                log::error!(
+                    "Failed to parse the shear journal of {}: {e}",
+                    root.display()
+                );
+                None
+            }
+        }
+    }
+
+    // written before anything in the install is touched. every planned path has to be inside the
+    // install, anything else could never be staged or rolled back
+    pub fn begin(root: &Path, plan: &ShearPlan, profile: Option<String>) -> Result<Self, String> {
+        let entries = plan
+            .steps
+            .iter()
+            .flat_map(|step| step.paths.iter().map(move |path| (step, path)))
+            .map(|(step, path)| {
+                let Ok(relative) = path.strip_prefix(root) else {
+                    return Err(format!(
+                        "{} is not inside the install, nothing was touched.",
+                        path.display()
+                    ));
+                };
+
+                Ok(JournalEntry {
+                    category: step.category.id().to_owned(),
+                    path: relative.to_path_buf(),
+                    staged: false,
+                    size: get_path_size(path),
+                    folder: path.is_dir(),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let journal = Self {
+            started: unix_now(),
+            phase: JournalPhase::Staging,
+            streaming_install_updated: false,
+            entries,
+            profile,
+            freed: 0,
+            root: root.to_path_buf(),
+        };
+
+        std::fs::create_dir_all(shears_folder(root))
+            .and_then(|()| journal.save())
+            .map_err(|e| format!("Failed to write the shear journal: {e}"))?;
+        Ok(journal)
+    }
+
+    // the journal is replaced atomically so a crash never leaves half of it on disk
+    fn save(&self) -> std::io::Result<()> {
+        let contents = toml::to_string_pretty(self).map_err(std::io::Error::other)?;
+
+        let path = Self::path(&self.root);
+        let temporary_path = path.with_extension("tmp");
+        std::fs::write(&temporary_path, contents)?;
+        std::fs::rename(temporary_path, path)
+    }
+
+    pub fn can_roll_back(&self) -> bool {
+        self.phase == JournalPhase::Staging
+    }
+
+    pub fn staged_count(&self) -> usize {
+        self.entries.iter().filter(|entry| entry.staged).count()
+    }
+
+    // a crash between moving a path and saving the journal leaves it staged but unrecorded,
+    // so while staging, what is in the staging folder is what counts
+    fn reconcile_staged(&mut self) {
+        let staging_folder = self.staging_folder();
+        for entry in &mut self.entries {
+            entry.staged = staging_folder.join(&entry.path).exists();
+        }
+    }
+
+    fn stage_entry(&self, guard: &DeletionGuard, entry: &JournalEntry) -> Result<(), GuardError> {
+        let original = self.root.join(&entry.path);
+        let staged = self.staging_folder().join(&entry.path);
+
+        match find_category(&entry.category) {
+            Some(category) => category.apply(guard, &original, &staged),
+            None => guard.move_into(&original, &staged),
+        }
+    }
+
+    fn update_streaming_install(&self) -> std::io::Result<()> {
+        let streaming_install_path = self.root.join(STREAMING_INSTALL_FILE_NAME);
+        let snapshot_path = self.snapshot_path();
+        if streaming_install_path.exists() && !snapshot_path.exists() {
+            std::fs::copy(&streaming_install_path, &snapshot_path)?;
+        }
+
+        let staged: Vec<PathBuf> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.staged)
+            .map(|entry| self.root.join(&entry.path))
+            .collect();
+
+        update_streaminginstall(&self.root, &staged)
+    }
+
+    fn finish(&self) -> std::io::Result<()> {
+        let snapshot_path = self.snapshot_path();
+        if snapshot_path.exists() {
+            std::fs::remove_file(snapshot_path)?;
+        }
+
+        std::fs::remove_file(Self::path(&self.root))
+    }
+
+    // the journal is gone at this point, a failure here only costs the history entry
+    fn write_ledger(&self) {
+        let staged = || self.entries.iter().filter(|entry| entry.staged);
+        if staged().next().is_none() {
+            return;
+        }
+
+        let entry = LedgerEntry {
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            date: unix_now(),
+            profile: self.profile.clone(),
+            categories: staged().map(|entry| entry.category.clone()).collect(),
+            files: staged()
+                .map(|entry| LedgerFile {
+                    path: entry.path.clone(),
+                    category: entry.category.clone(),
+                    size: entry.size,
+                    folder: entry.folder,
+                })
+                .collect(),
+            freed: self.freed,
+        };
+        if let Err(e) = ShearLedger::append(&self.root, entry) {
+            log::warn!("Unable to write the shear ledger: {e}");
+        }
+    }
+
+    // does whatever is left to do, so it is used both for new shears and to resume interrupted ones
+    pub fn run(
+        &mut self,
+        guard: &DeletionGuard,
+        progress: &JobProgress,
+    ) -> Result<JournalOutcome, String> {
+        let mut outcome = JournalOutcome::default();
+        let save_error = |e: std::io::Error| format!("Failed to update the shear journal: {e}");
+
+        if self.phase == JournalPhase::Staging {
+            self.reconcile_staged();
+            for index in 0..self.entries.len() {
+                let Some(entry) = self.entries.get(index).filter(|entry| !entry.staged) else {
+                    continue;
+                };
+
+                if progress.is_cancelled() {
+                    outcome.cancelled = true;
+                    return Ok(outcome);
+                }
+                progress.set_current(entry.path.display().to_string());
+
+                match self.stage_entry(guard, entry) {
+                    Ok(()) => {
+                        if let Some(entry) = self.entries.get_mut(index) {
+                            entry.staged = true;
+                        }
+                        self.save().map_err(save_error)?;
+                    }
+                    Err(e) => {
+                        log::warn!("Unable to delete because {e}.");
+                        outcome.failures.push(e.to_string());
+                    }
+                }
+            }
+
+            // nothing is deleted unless every path could be moved aside, the journal stays behind
+            // so the shear can be resumed once the files are free, or rolled back
+            if !outcome.failures.is_empty() {
+                return Ok(outcome);
+            }
+
+            if !self.streaming_install_updated {
+                self.update_streaming_install()
+                    .map_err(|e| format!("Failed to update streaminginstall.ini: {e}"))?;
+                self.streaming_install_updated = true;
+                self.save().map_err(save_error)?;
+            }
+
+            self.phase = JournalPhase::Committing;
+            self.save().map_err(save_error)?;
+        }
+
+        // only what the journal accounts for is deleted, anything else in the staging folder stays
+        let staged: Vec<PathBuf> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.staged)
+            .map(|entry| self.staging_folder().join(&entry.path))
+            .filter(|path| path.exists())
+            .collect();
+        let bytes_before = progress.bytes();
+        let reclaimable: u64 = staged
+            .iter()
+            .map(|path| path_usage(path, &mut Vec::new()).reclaimable)
+            .sum();
+        progress.set_total_bytes(bytes_before + reclaimable);
+        let removal = staged
+            .iter()
+            .try_for_each(|path| guard.remove(path, progress));
+        self.freed += progress.bytes() - bytes_before;
+        removal.map_err(|e| format!("Failed to delete the staged files: {e}"))?;
+
+        if progress.is_cancelled() {
+            self.save().map_err(save_error)?;
+            outcome.cancelled = true;
+            return Ok(outcome);
+        }
+        self.clean_up_staging_folder();
+
+        self.finish().map_err(save_error)?;
+        self.write_ledger();
+
+        outcome.removed = self
+            .entries
+            .iter()
+            .filter(|entry| entry.staged)
+            .map(|entry| self.root.join(&entry.path))
+            .collect();
+        Ok(outcome)
+    }
+
+    // puts every staged path back where it was and restores streaminginstall.ini
+    pub fn roll_back(&mut self) -> Result<(), String> {
+        if !self.can_roll_back() {
+            return Err(
+                "The staged files were already being deleted, the shear can only be resumed."
+                    .to_owned(),
+            );
+        }
+
+        self.reconcile_staged();
+        let mut failures = Vec::new();
+        for entry in self.entries.iter().filter(|entry| entry.staged) {
+            let original = self.root.join(&entry.path);
+            let staged = self.staging_folder().join(&entry.path);
+
+            if let Err(e) = std::fs::rename(&staged, &original) {
+                failures.push(format!("{}: {e}", original.display()));
+            }
+        }
+
+        let snapshot_path = self.snapshot_path();
+        if self.streaming_install_updated
+            && snapshot_path.exists()
+            && let Err(e) =
+                std::fs::copy(&snapshot_path, self.root.join(STREAMING_INSTALL_FILE_NAME))
+        {
+            failures.push(format!("{STREAMING_INSTALL_FILE_NAME}: {e}"));
+        }
+
+        if !failures.is_empty() {
+            return Err(format!(
+                "Some files could not be restored:\n{}",
+                failures.join("\n")
+            ));
+        }
+
+        self.finish()
+            .map_err(|e| format!("Failed to remove the shear journal: {e}"))?;
+
+        if !self.clean_up_staging_folder() {
+            return Err(format!(
+                "The shear was rolled back, but {} still holds files that aren't part of it. They were left in place.",
+                self.staging_folder().display()
+            ));
+        }
+        Ok(())
+    }
+
+    // removes the staging folder once nothing but empty folders is left in it
+    fn clean_up_staging_folder(&self) -> bool {
+        let staging_folder = self.staging_folder();
+        !staging_folder.exists() || remove_empty_folders(&staging_folder)
+    }
+}
+
+// everything a shear does once the plan is chosen, meant to run on a worker thread
+pub fn shear(
+    root: &Path,
+    game: &GameDefinition,
+    plan: &ShearPlan,
+    profile: Option<String>,
+    progress: &JobProgress,
+) -> Result<JournalOutcome, String> {
+    // a new journal would replace the old one and delete its staged files with the new ones
+    if Journal::exists(root) {
+        return Err(
+            "This install has an interrupted shear, resume or roll it back first.".to_owned(),
+        );
+    }
+
+    // nothing is touched unless every category and the guard agree with the plan
+    plan.check(root)?;
+    let guard = DeletionGuard::new(root, game).map_err(|e| e.to_string())?;
+    plan.check_guard(&guard)?;
+
+    let mut journal = Journal::begin(root, plan, profile)?;
+    journal.run(&guard, progress)
+}
+
+pub fn resume_shear(
+    root: &Path,
+    game: &GameDefinition,
+    progress: &JobProgress,
+) -> Result<JournalOutcome, String> {
+    let mut journal = Journal::load(root).ok_or("No interrupted shear found.")?;
+    let guard = DeletionGuard::new(root, game).map_err(|e| e.to_string())?;
+    journal.run(&guard, progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::categories::{ShearPlanStep, VideosCategory};
+
+    fn test_install() -> tempfile::TempDir {
+        let install = tempfile::tempdir().expect("Failed to create the test install");
+        let videos = install.path().join("videos");
+        std::fs::create_dir_all(&videos).expect("Failed to create the test install");
+        std::fs::write(videos.join("intro.bik"), "video")
+            .expect("Failed to create the test install");
+        install
+    }
+
+    // begins a shear of the videos folder and moves it aside without recording it,
+    // like a crash right after the move would
+    fn crash_after_move(root: &Path) {
+        let plan = ShearPlan {
+            steps: vec![ShearPlanStep {
+                category: &VideosCategory,
+                paths: vec![root.join("videos")],
+            }],
+        };
+        let journal = Journal::begin(root, &plan, None).expect("Failed to begin the shear");
+        std::fs::create_dir_all(journal.staging_folder()).expect("Failed to create staging");
+        std::fs::rename(root.join("videos"), journal.staging_folder().join("videos"))
+            .expect("Failed to stage the videos");
+    }
+
+    #[test]
+    fn roll_back_restores_unrecorded_moves() {
+        let install = test_install();
+        let root = install.path();
+        crash_after_move(root);
+
+        let mut journal = Journal::load(root).expect("The journal should exist");
+        assert_eq!(journal.staged_count(), 0, "the move was never recorded");
+        journal.roll_back().expect("Failed to roll back");
+
+        assert!(
+            root.join("videos").join("intro.bik").is_file(),
+            "the video should be back"
+        );
+        assert!(!Journal::exists(root), "the journal should be gone");
+    }
+
+    #[test]
+    fn roll_back_keeps_files_it_does_not_know() {
+        let install = test_install();
+        let root = install.path();
+        crash_after_move(root);
+        let foreign = shears_folder(root)
+            .join(STAGING_FOLDER_NAME)
+            .join("other.forge");
+        std::fs::write(&foreign, "forge").expect("Failed to create the foreign file");
+
+        let mut journal = Journal::load(root).expect("The journal should exist");
+        assert!(
+            journal.roll_back().is_err(),
+            "the leftover file should be reported"
+        );
+
+        assert!(
+            root.join("videos").join("intro.bik").is_file(),
+            "the video should be back"
+        );
+        assert!(foreign.is_file(), "the foreign file should be kept");
+    }
+
+    #[test]
+    fn resume_records_unrecorded_moves() {
+        let install = test_install();
+        let root = install.path();
+        crash_after_move(root);
+
+        let outcome = resume_shear(root, &GameDefinition::default(), &JobProgress::default())
+            .expect("Failed to resume");
+        assert!(outcome.failures.is_empty(), "{:?}", outcome.failures);
+        assert_eq!(outcome.removed, vec![root.join("videos")]);
+
+        let ledger = ShearLedger::load(root);
+        let entry = ledger.last().expect("The shear should be in the ledger");
+        assert_eq!(
+            entry.files.first().map(|file| file.path.clone()),
+            Some(PathBuf::from("videos"))
+        );
+        assert!(
+            !shears_folder(root).join(STAGING_FOLDER_NAME).exists(),
+            "the staging folder should be gone"
+        );
+    }
+
+    #[test]
+    fn shear_refuses_while_interrupted() {
+        let install = test_install();
+        let root = install.path();
+        crash_after_move(root);
+
+        let plan = ShearPlan::default();
+        let result = shear(
+            root,
+            &GameDefinition::default(),
+            &plan,
+            None,
+            &JobProgress::default(),
+        );
+        assert!(result.is_err(), "a second shear must not start");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hard_links_free_nothing() {
+        let install = test_install();
+        let root = install.path();
+        std::fs::hard_link(
+            root.join("videos").join("intro.bik"),
+            root.join("intro.bik"),
+        )
+        .expect("Failed to link the video");
+
+        let plan = ShearPlan {
+            steps: vec![ShearPlanStep {
+                category: &VideosCategory,
+                paths: vec![root.join("videos")],
+            }],
+        };
+        let outcome = shear(
+            root,
+            &GameDefinition::default(),
+            &plan,
+            None,
+            &JobProgress::default(),
+        )
+        .expect("Failed to shear");
+        assert!(outcome.failures.is_empty(), "{:?}", outcome.failures);
+
+        let ledger = ShearLedger::load(root);
+        let entry = ledger.last().expect("The shear should be in the ledger");
+        assert_eq!(entry.freed, 0, "the linked video is still on the drive");
+    }
+
+    #[test]
+    fn begin_refuses_paths_outside_the_install() {
+        let install = test_install();
+        let outside = tempfile::tempdir().expect("Failed to create the outside folder");
+        let plan = ShearPlan {
+            steps: vec![ShearPlanStep {
+                category: &VideosCategory,
+                paths: vec![install.path().join("videos"), outside.path().to_path_buf()],
+            }],
+        };
+
+        assert!(Journal::begin(install.path(), &plan, None).is_err());
+        assert!(
+            !Journal::exists(install.path()),
+            "no journal should be written"
+        );
+    }
+
+    #[test]
+    fn staging_failures_delete_nothing() {
+        let install = test_install();
+        let root = install.path();
+        let plan = ShearPlan {
+            steps: vec![ShearPlanStep {
+                category: &VideosCategory,
+                paths: vec![root.join("videos"), root.join("missing.bik")],
+            }],
+        };
+
+        let mut journal = Journal::begin(root, &plan, None).expect("Failed to begin the shear");
+        let guard =
+            DeletionGuard::new(root, &GameDefinition::default()).expect("Failed to make a guard");
+        let outcome = journal
+            .run(&guard, &JobProgress::default())
+            .expect("Failed to run the shear");
+        assert_eq!(outcome.failures.len(), 1, "{:?}", outcome.failures);
+
+        let mut journal = Journal::load(root).expect("The journal should be left behind");
+        assert!(
+            journal.can_roll_back(),
+            "nothing should have been committed"
+        );
+        journal.roll_back().expect("Failed to roll back");
+        assert!(
+            root.join("videos").join("intro.bik").is_file(),
+            "the video should be back"
+        );
+    }
+}
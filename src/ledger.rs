@@ -0,0 +1,83 @@
+use std::{
+    collections::BTreeSet,
+    io::Write as _,
+    path::{Path, PathBuf},
+};
+
+use crate::journal::shears_folder;
+
+const LEDGER_FILE_NAME: &str = "history.toml";
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct LedgerFile {
+    pub path: PathBuf, // relative to the install, folders are listed as a whole
+    pub category: String,
+    pub size: u64,
+    pub folder: bool,
+}
+
+// one finished shear, as recorded inside the install it was done to
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct LedgerEntry {
+    pub version: String,
+    pub date: u64,
+    pub profile: Option<String>,
+    pub categories: BTreeSet<String>,
+    pub files: Vec<LedgerFile>,
+    pub freed: u64, // reclaimable, not what the file lengths add up to
+}
+
+// every shear an install went through, oldest first. the file is only ever appended to
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ShearLedger {
+    pub shears: Vec<LedgerEntry>,
+}
+
+impl ShearLedger {
+    fn path(root: &Path) -> PathBuf {
+        shears_folder(root).join(LEDGER_FILE_NAME)
+    }
+
+    pub fn load(root: &Path) -> Self {
+        let Ok(contents) = std::fs::read_to_string(Self::path(root)) else {
+            return Self::default();
+        };
+
+        toml::from_str(&contents).unwrap_or_else(|e| {
+            log::error!(
+                "Failed to parse the shear ledger of {}: {e}",
+                root.display()
+            );
+            Self::default()
+        })
+    }
+
+    // each entry is its own `[[shears]]` table, so appending it keeps the file valid
+    pub fn append(root: &Path, entry: LedgerEntry) -> std::io::Result<()> {
+        let contents = toml::to_string_pretty(&Self {
+            shears: vec![entry],
+        })
+        .map_err(std::io::Error::other)?;
+
+        std::fs::create_dir_all(shears_folder(root))?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(Self::path(root))?;
+        writeln!(file, "{contents}")
+    }
+
+    pub fn last(&self) -> Option<&LedgerEntry> {
+        self.shears.last()
+    }
+
+    pub fn removed_categories(&self) -> BTreeSet<String> {
+        self.shears
+            .iter()
+            .flat_map(|entry| entry.categories.iter().cloned())
+            .collect()
+    }
+}
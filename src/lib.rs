@@ -1,9 +1,30 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
 pub use app::ShearsApp;
+pub use cli::run_cli;
 
 mod app;
+mod batch;
+mod categories;
+mod cli;
+mod compare;
+mod depgraph;
+mod diskusage;
+mod filelist;
+mod forge;
+mod games;
+mod gamesettings;
+mod guard;
 mod helpers;
+mod installs;
+mod journal;
+mod ledger;
+mod planner;
+mod profiles;
+mod progress;
 mod scan;
 mod settings;
+mod shearstate;
+mod sizecache;
+mod streaminginstall;
 mod types;
@@ -0,0 +1,342 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    categories::{ShearCategory, ShearSelection},
+    diskusage::DiskUsage,
+    games::{GameDefinition, detect_game},
+    helpers::{get_mount_point, get_shearing_features_availability},
+    journal::Journal,
+    progress::JobProgress,
+    sizecache::SizeCache,
+    types::ShearingFeaturesAvailability,
+};
+
+#[derive(Debug)]
+pub struct SurveyedInstall {
+    pub folder: PathBuf,
+    pub mount_point: Option<PathBuf>,
+    pub features_availability: ShearingFeaturesAvailability,
+}
+
+// every known install analyzed once, the plan itself is cheap and redone whenever the target changes
+#[derive(Debug, Default)]
+pub struct SpaceSurvey {
+    pub installs: Vec<SurveyedInstall>,
+    pub skipped: Vec<(PathBuf, String)>, // installs a batch would refuse anyway
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CutId {
+    pub folder: PathBuf,
+    pub category: String,
+    pub key: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct PlannedCut {
+    pub id: CutId,
+    pub category: &'static dyn ShearCategory,
+    pub label: String,
+    pub priority: u32,
+    pub size: DiskUsage,
+}
+
+#[derive(Debug, Default)]
+pub struct SpacePlan {
+    pub cuts: Vec<PlannedCut>,
+    pub excluded: Vec<PlannedCut>, // taken out by the user, shown so they can be put back
+    pub freed: u64,
+    pub reaches_target: bool,
+}
+
+impl SpacePlan {
+    // one selection per install, in the form a batch takes
+    pub fn selections(&self) -> Vec<(PathBuf, ShearSelection)> {
+        let mut selections: Vec<(PathBuf, ShearSelection)> = Vec::new();
+        for cut in &self.cuts {
+            let index = if let Some(index) = selections
+                .iter()
+                .position(|(folder, _)| *folder == cut.id.folder)
+            {
+                index
+            } else {
+                selections.push((cut.id.folder.clone(), ShearSelection::default()));
+                selections.len() - 1
+            };
+
+            let (_, selection) = selections
+                .get_mut(index)
+                .expect("SpacePlan.selections: Out of bounds error");
+            selection
+                .removed
+                .entry(cut.id.category.clone())
+                .or_default()
+                .insert(cut.id.key.clone());
+        }
+        selections
+    }
+
+    pub fn cuts_for(&self, folder: &Path) -> impl Iterator<Item = &PlannedCut> {
+        self.cuts.iter().filter(move |cut| cut.id.folder == folder)
+    }
+}
+
+pub fn survey_installs(
+    folders: &[PathBuf],
+    definitions: &[GameDefinition],
+    cache: &SizeCache,
+    progress: &JobProgress,
+) -> SpaceSurvey {
+    let mut survey = SpaceSurvey::default();
+
+    for (index, folder) in folders.iter().enumerate() {
+        if progress.is_cancelled() {
+            break;
+        }
+        progress.set_stage(format!(
+            "Install {} of {}: {}",
+            index + 1,
+            folders.len(),
+            folder.display()
+        ));
+
+        if Journal::exists(folder) {
+            survey.skipped.push((
+                folder.clone(),
+                "Has an interrupted shear, resume or roll it back first.".to_owned(),
+            ));
+            continue;
+        }
+
+        let game = detect_game(definitions, folder);
+        let features_availability =
+            get_shearing_features_availability(folder, &game.shear_categories(), cache, progress);
+
+        if !features_availability.has_forge_files {
+            survey.skipped.push((
+                folder.clone(),
+                "Folder does not contain FORGE files.".to_owned(),
+            ));
+        } else if !features_availability.damaged_forges.is_empty() {
+            survey.skipped.push((
+                folder.clone(),
+                "Some forge files are damaged, verify the game files first.".to_owned(),
+            ));
+        } else {
+            survey.installs.push(SurveyedInstall {
+                folder: folder.clone(),
+                mount_point: get_mount_point(folder),
+                features_availability,
+            });
+        }
+    }
+
+    survey
+}
+
+// everything the planner is allowed to cut, least destructive first and the biggest installs first
+// within a priority, so "drop Ultra everywhere" reaches the target with as few installs touched as possible
+fn candidate_cuts(survey: &SpaceSurvey, mount_point: Option<&Path>) -> Vec<PlannedCut> {
+    let mut candidates: Vec<PlannedCut> = survey
+        .installs
+        .iter()
+        .filter(|install| mount_point.is_none() || install.mount_point.as_deref() == mount_point)
+        .flat_map(|install| {
+            let folder = &install.folder;
+            install
+                .features_availability
+                .categories
+                .iter()
+                .flat_map(move |availability| {
+                    availability
+                        .items
+                        .iter()
+                        .filter(|item| item.is_available() && item.size.reclaimable > 0)
+                        .filter_map(move |item| {
+                            Some(PlannedCut {
+                                id: CutId {
+                                    folder: folder.clone(),
+                                    category: availability.category.id().to_owned(),
+                                    key: item.key.clone(),
+                                },
+                                category: availability.category,
+                                label: item.label.clone(),
+                                priority: availability.category.cut_priority(&item.key)?,
+                                size: item.size,
+                            })
+                        })
+                })
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| {
+        a.priority
+            .cmp(&b.priority)
+            .then_with(|| b.size.reclaimable.cmp(&a.size.reclaimable))
+    });
+    candidates
+}
+
+// greedy: takes cuts in priority order until the target is reached. a cut only happens once every
+// item of its category that goes before it is cut too, which keeps texture tiers contiguous.
+// with a mount point only the installs on that drive are considered
+pub fn plan_space(
+    survey: &SpaceSurvey,
+    mount_point: Option<&Path>,
+    target: u64,
+    excluded: &BTreeSet<CutId>,
+) -> SpacePlan {
+    let candidates = candidate_cuts(survey, mount_point);
+    let mut plan = SpacePlan::default();
+
+    for candidate in &candidates {
+        if plan.freed >= target {
+            break;
+        }
+        if excluded.contains(&candidate.id) {
+            plan.excluded.push(candidate.clone());
+            continue;
+        }
+
+        let blocked = candidates.iter().any(|earlier| {
+            earlier.id.folder == candidate.id.folder
+                && earlier.id.category == candidate.id.category
+                && earlier.priority < candidate.priority
+                && !plan.cuts.iter().any(|cut| cut.id == earlier.id)
+        });
+        if blocked {
+            continue;
+        }
+
+        plan.freed += candidate.size.reclaimable;
+        plan.cuts.push(candidate.clone());
+    }
+
+    plan.reaches_target = plan.freed >= target;
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::categories::{CategoryAvailability, TexturesCategory, VideosCategory};
+
+    fn sized(category: &'static dyn ShearCategory, sizes: &[u64]) -> CategoryAvailability {
+        let mut items = category.known_items();
+        for (item, &size) in items.iter_mut().zip(sizes) {
+            item.size = DiskUsage {
+                apparent: size,
+                reclaimable: size,
+                linked_files: 0,
+            };
+        }
+        CategoryAvailability { category, items }
+    }
+
+    // texture sizes from Low to Ultra
+    fn install(folder: &str, mount_point: &str, textures: &[u64], videos: u64) -> SurveyedInstall {
+        SurveyedInstall {
+            folder: PathBuf::from(folder),
+            mount_point: Some(PathBuf::from(mount_point)),
+            features_availability: ShearingFeaturesAvailability {
+                has_forge_files: true,
+                categories: vec![
+                    sized(&TexturesCategory, textures),
+                    sized(&VideosCategory, &[videos]),
+                ],
+                ..Default::default()
+            },
+        }
+    }
+
+    fn survey() -> SpaceSurvey {
+        SpaceSurvey {
+            installs: vec![
+                install("a", "/mnt/a", &[50, 40, 30, 5, 10], 3),
+                install("b", "/mnt/b", &[50, 40, 30, 0, 20], 0),
+            ],
+            skipped: Vec::new(),
+        }
+    }
+
+    fn cut_ids(plan: &SpacePlan) -> Vec<(&str, &str, &str)> {
+        plan.cuts
+            .iter()
+            .map(|cut| {
+                (
+                    cut.id.folder.to_str().unwrap_or_default(),
+                    cut.id.category.as_str(),
+                    cut.id.key.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cuts_least_destructive_and_biggest_first() {
+        let plan = plan_space(&survey(), None, 25, &BTreeSet::new());
+        assert_eq!(
+            cut_ids(&plan),
+            vec![("b", "textures", "4"), ("a", "textures", "4")]
+        );
+        assert_eq!(plan.freed, 30);
+        assert!(plan.reaches_target);
+
+        let plan = plan_space(&survey(), None, 33, &BTreeSet::new());
+        assert_eq!(
+            cut_ids(&plan),
+            vec![
+                ("b", "textures", "4"),
+                ("a", "textures", "4"),
+                ("a", "videos", "videos"),
+            ]
+        );
+    }
+
+    #[test]
+    fn exclusions_block_the_tiers_below() {
+        let excluded = BTreeSet::from([CutId {
+            folder: PathBuf::from("a"),
+            category: "textures".to_owned(),
+            key: "4".to_owned(),
+        }]);
+
+        let plan = plan_space(&survey(), None, u64::MAX, &excluded);
+        assert_eq!(plan.excluded.len(), 1);
+        assert!(
+            !plan
+                .cuts
+                .iter()
+                .any(|cut| cut.id.folder == Path::new("a") && cut.id.category == "textures"),
+            "Very High can't go while Ultra stays: {:?}",
+            cut_ids(&plan)
+        );
+    }
+
+    #[test]
+    fn never_cuts_low_textures() {
+        let plan = plan_space(&survey(), None, u64::MAX, &BTreeSet::new());
+        assert!(!plan.reaches_target);
+        assert!(
+            !plan.cuts.iter().any(|cut| cut.id.key == "0"),
+            "{:?}",
+            cut_ids(&plan)
+        );
+        assert_eq!(plan.freed, 3 + 5 + 10 + 30 + 40 + 20 + 30 + 40);
+    }
+
+    #[test]
+    fn only_plans_for_the_chosen_drive() {
+        let plan = plan_space(&survey(), Some(Path::new("/mnt/b")), 100, &BTreeSet::new());
+        assert!(
+            plan.cuts.iter().all(|cut| cut.id.folder == Path::new("b")),
+            "{:?}",
+            cut_ids(&plan)
+        );
+        assert_eq!(plan.freed, 20 + 30 + 40);
+        assert!(!plan.reaches_target);
+    }
+}
@@ -0,0 +1,168 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    categories::{CategoryAvailability, ShearSelection},
+    settings::get_config_dir,
+};
+
+// a named selection like "competitive minimal", stored as its own TOML file so it can be shared
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct ShearProfile {
+    pub name: String,
+    #[serde(default)]
+    pub selection: ShearSelection,
+}
+
+fn profiles_folder() -> PathBuf {
+    get_config_dir().join("profiles")
+}
+
+impl ShearProfile {
+    // captures the checkboxes of an analyzed install. only items this install has are recorded, an
+    // unchecked box for something that isn't there says nothing about what to keep elsewhere
+    pub fn from_selection(
+        name: &str,
+        categories: &[CategoryAvailability],
+        checkboxes: &[Vec<bool>],
+    ) -> Self {
+        let mut selection = ShearSelection::default();
+
+        for (availability, keep) in categories.iter().zip(checkboxes) {
+            let category = availability.category;
+            let removed: std::collections::BTreeSet<String> = availability
+                .items
+                .iter()
+                .zip(keep)
+                .filter(|&(item, &kept)| !kept && item.is_available())
+                .map(|(item, _)| item.key.clone())
+                .collect();
+
+            let available = availability
+                .items
+                .iter()
+                .filter(|item| item.is_available())
+                .count();
+            let has_known_items = !category.known_items().is_empty();
+            if !has_known_items && !removed.is_empty() && removed.len() == available {
+                selection.remove_all.insert(category.id().to_owned());
+            } else if !removed.is_empty() {
+                selection.removed.insert(category.id().to_owned(), removed);
+            }
+        }
+
+        Self {
+            name: name.to_owned(),
+            selection,
+        }
+    }
+
+    pub fn file_name(&self) -> String {
+        let stem: String = self
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{stem}.toml")
+    }
+
+    // the profile already saved under the same file, names that only differ in case or in
+    // characters that don't fit a file name end up there too
+    pub fn find_existing<'a>(&self, profiles: &'a [Self]) -> Option<&'a Self> {
+        let file_name = self.file_name();
+        profiles
+            .iter()
+            .find(|profile| profile.file_name().eq_ignore_ascii_case(&file_name))
+    }
+
+    pub fn load_all() -> Vec<Self> {
+        let Ok(entries) = std::fs::read_dir(profiles_folder()) else {
+            return Vec::new();
+        };
+
+        let mut profiles: Vec<Self> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
+            })
+            .filter_map(|path| match Self::read(&path) {
+                Ok(profile) => Some(profile),
+                Err(e) => {
+                    log::error!("Failed to load profile {}: {e}", path.display());
+                    None
+                }
+            })
+            .collect();
+
+        profiles.sort_by(|a, b| a.name.cmp(&b.name));
+        profiles
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let profile: Self = toml::from_str(&contents).map_err(|e| e.to_string())?;
+
+        if profile.name.trim().is_empty() {
+            return Err("The profile has no name.".to_owned());
+        }
+        Ok(profile)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let contents = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, contents).map_err(|e| e.to_string())
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let folder = profiles_folder();
+        std::fs::create_dir_all(&folder).map_err(|e| e.to_string())?;
+        self.write(&folder.join(self.file_name()))
+    }
+
+    pub fn delete(&self) -> Result<(), String> {
+        std::fs::remove_file(profiles_folder().join(self.file_name())).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::categories::{CategoryItem, RendererCategory};
+
+    #[test]
+    fn from_selection_skips_missing_items() {
+        let mut vulkan = CategoryItem::new("vulkan", "Vulkan renderer");
+        vulkan.size.apparent = 1;
+        let categories = [CategoryAvailability {
+            category: &RendererCategory,
+            items: vec![vulkan, CategoryItem::new("dx11", "DirectX 11 renderer")],
+        }];
+
+        let profile =
+            ShearProfile::from_selection("vulkan only", &categories, &[vec![true, false]]);
+        assert_eq!(profile.selection, ShearSelection::default());
+    }
+
+    #[test]
+    fn find_existing_matches_file_names() {
+        let existing = [ShearProfile {
+            name: "a b".to_owned(),
+            ..ShearProfile::default()
+        }];
+        let profile = |name: &str| ShearProfile {
+            name: name.to_owned(),
+            ..ShearProfile::default()
+        };
+
+        assert!(profile("a_b").find_existing(&existing).is_some());
+        assert!(profile("A B").find_existing(&existing).is_some());
+        assert!(profile("ab").find_existing(&existing).is_none());
+    }
+}
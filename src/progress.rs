@@ -0,0 +1,72 @@
+use std::sync::{
+    Mutex,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+// shared between a worker thread and the ui, the worker reports here and checks for cancellation between files
+#[derive(Debug, Default)]
+pub struct JobProgress {
+    cancelled: AtomicBool,
+    files: AtomicU64,
+    bytes: AtomicU64,
+    total_bytes: AtomicU64,
+    stage: Mutex<String>, // which part of a longer job is running, e.g. one install of a batch
+    current: Mutex<String>,
+}
+
+impl JobProgress {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_total_bytes(&self, total_bytes: u64) {
+        self.total_bytes.store(total_bytes, Ordering::Relaxed);
+    }
+
+    pub fn set_stage(&self, stage: impl Into<String>) {
+        if let Ok(mut guard) = self.stage.lock() {
+            *guard = stage.into();
+        }
+    }
+
+    pub fn set_current(&self, current: impl Into<String>) {
+        if let Ok(mut guard) = self.current.lock() {
+            *guard = current.into();
+        }
+    }
+
+    pub fn add_file(&self, bytes: u64) {
+        self.files.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn files(&self) -> u64 {
+        self.files.load(Ordering::Relaxed)
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn stage(&self) -> String {
+        self.stage
+            .lock()
+            .map(|guard| guard.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn current(&self) -> String {
+        self.current
+            .lock()
+            .map(|guard| guard.clone())
+            .unwrap_or_default()
+    }
+}
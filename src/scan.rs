@@ -1,3 +1,5 @@
+use crate::games::GameDefinition;
+
 fn is_restricted_or_junk(name: &std::ffi::OsStr) -> bool {
     let Some(name_str) = name.to_str() else {
         return false;
@@ -29,6 +31,7 @@ fn is_restricted_or_junk(name: &std::ffi::OsStr) -> bool {
 
 pub fn scan_recursive(
     dir: &std::path::Path,
+    definitions: &[GameDefinition],
     results: &mut Vec<std::path::PathBuf>,
     stop_flag: &std::sync::atomic::AtomicBool,
 ) {
@@ -46,8 +49,7 @@ pub fn scan_recursive(
         }
     };
 
-    let mut found_forge = false;
-    let mut found_exe = false;
+    let mut file_names = Vec::new();
     let mut subdirs = Vec::new();
 
     for entry in entries.filter_map(|e| e.ok()) {
@@ -57,21 +59,20 @@ pub fn scan_recursive(
 
         let file_name = entry.file_name();
 
-        if file_name == "datapc64.forge" {
-            found_forge = true;
-        } else if file_name == "RainbowSix.exe" {
-            found_exe = true;
-        }
-
         if let Ok(ft) = entry.file_type()
             && ft.is_dir()
             && !is_restricted_or_junk(&file_name)
         {
             subdirs.push(entry.path());
         }
+
+        file_names.push(file_name);
     }
 
-    if found_forge && found_exe {
+    if definitions
+        .iter()
+        .any(|definition| definition.matches_file_names(&file_names))
+    {
         log::info!("FOUND IN: {}", dir.display());
         results.push(dir.to_path_buf());
     }
@@ -80,6 +81,6 @@ pub fn scan_recursive(
         if stop_flag.load(std::sync::atomic::Ordering::Relaxed) {
             return;
         }
-        scan_recursive(&sub, results, stop_flag);
+        scan_recursive(&sub, definitions, results, stop_flag);
     }
 }
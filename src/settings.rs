@@ -1,11 +1,24 @@
 use std::path::PathBuf;
 
+// falls back to the working directory if the appdata folder can't be found
+pub fn get_config_dir() -> PathBuf {
+    if let Some(proj_dirs) = directories::ProjectDirs::from("", "", "shears") {
+        proj_dirs.config_dir().to_path_buf()
+    } else {
+        log::warn!("Failed to get appdata folder");
+        PathBuf::new()
+    }
+}
+
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)]
 pub struct PersistentSettingsStorage {
     pub enable_shears_update_check_on_startup: bool,
     pub use_loose_selection: bool,
     pub enable_experimental_features: bool,
+    pub enable_advanced_selection: bool,
+    // installs with a shear journal that hasn't finished yet, checked on startup
+    pub pending_journals: Vec<PathBuf>,
 }
 
 impl Default for PersistentSettingsStorage {
@@ -14,6 +27,8 @@ impl Default for PersistentSettingsStorage {
             enable_shears_update_check_on_startup: true,
             use_loose_selection: false,
             enable_experimental_features: false,
+            enable_advanced_selection: false,
+            pending_journals: Vec::new(),
         }
     }
 }
@@ -26,12 +41,7 @@ impl PersistentSettingsStorage {
             "UserSettings.toml"
         };
 
-        if let Some(proj_dirs) = directories::ProjectDirs::from("", "", "shears") {
-            proj_dirs.config_dir().join(settings_file_name)
-        } else {
-            log::warn!("Failed to get appdata folder");
-            PathBuf::from(settings_file_name)
-        }
+        get_config_dir().join(settings_file_name)
     }
 
     pub fn load_or_default() -> Self {
@@ -51,6 +61,25 @@ impl PersistentSettingsStorage {
         Self::default()
     }
 
+    pub fn add_pending_journal(&mut self, folder: &std::path::Path) {
+        if !self
+            .pending_journals
+            .iter()
+            .any(|pending| pending == folder)
+        {
+            self.pending_journals.push(folder.to_path_buf());
+            self.save_to_file();
+        }
+    }
+
+    pub fn remove_pending_journal(&mut self, folder: &std::path::Path) {
+        let count = self.pending_journals.len();
+        self.pending_journals.retain(|pending| pending != folder);
+        if self.pending_journals.len() != count {
+            self.save_to_file();
+        }
+    }
+
     pub fn save_to_file(&self) {
         let path = Self::get_path();
 
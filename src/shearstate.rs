@@ -0,0 +1,339 @@
+use std::{collections::BTreeSet, path::Path};
+
+use crate::{
+    categories::{ShearCategory as _, TexturesCategory, VideosCategory, find_category},
+    filelist::DepotFileList,
+    helpers::format_timestamp,
+    ledger::ShearLedger,
+    streaminginstall::StreamingInstall,
+    types::{ForgeTextureQualityLevel, ShearingFeaturesAvailability},
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct ShearState {
+    pub ledger: ShearLedger,
+    pub streaming_install_version: Option<String>, // the Shears version that rewrote streaminginstall.ini
+    pub lowest_texture_level: Option<ForgeTextureQualityLevel>,
+    pub highest_texture_level: Option<ForgeTextureQualityLevel>,
+    pub missing: Vec<String>, // item labels, e.g. "Ultra Textures" or "Videos"
+    pub missing_categories: BTreeSet<String>,
+    pub has_texture_gaps: bool, // tiers are missing that the texture check would never let Shears remove
+    pub inconsistencies: Vec<String>,
+    pub redownload: DepotFileList, // what is still missing of the files the ledger lists
+}
+
+impl ShearState {
+    pub fn is_sheared(&self) -> bool {
+        !self.ledger.shears.is_empty()
+            || self.streaming_install_version.is_some()
+            || !self.missing.is_empty()
+    }
+
+    // e.g. "Sheared to High on 2025-01-31 18:00 UTC, videos removed"
+    pub fn summary(&self) -> String {
+        if !self.is_sheared() {
+            return "Not sheared, every texture tier and the videos are present.".to_owned();
+        }
+
+        let mut summary = if self.has_texture_gaps {
+            "Partially sheared".to_owned()
+        } else if self.missing_categories.contains(TexturesCategory.id())
+            && let Some(lowest) = self.lowest_texture_level
+            && let Some(highest) = self.highest_texture_level
+        {
+            if lowest == ForgeTextureQualityLevel::Low || lowest == highest {
+                format!("Sheared to {highest}")
+            } else {
+                format!("Sheared to {lowest} - {highest}")
+            }
+        } else {
+            "Sheared".to_owned()
+        };
+
+        if let Some(last) = self.ledger.last() {
+            summary.push_str(&format!(" on {}", format_timestamp(last.date)));
+        } else if let Some(version) = &self.streaming_install_version {
+            summary.push_str(&format!(" by Shears {version}"));
+        } else {
+            summary.push_str(" (detected from missing files)");
+        }
+
+        let mut removed: BTreeSet<String> = self
+            .missing_categories
+            .iter()
+            .filter(|&category| category != TexturesCategory.id())
+            .cloned()
+            .collect();
+        removed.extend(
+            self.ledger
+                .removed_categories()
+                .into_iter()
+                .filter(|category| {
+                    category != TexturesCategory.id() && category != VideosCategory.id()
+                }),
+        );
+        for category in removed.iter().filter_map(|id| find_category(id)) {
+            summary.push_str(&format!(", {} removed", category.name().to_lowercase()));
+        }
+
+        summary
+    }
+}
+
+// texture tiers as they are now from Low up, with whether each one is there
+fn texture_tiers(features: &ShearingFeaturesAvailability) -> Vec<(ForgeTextureQualityLevel, bool)> {
+    features
+        .categories
+        .iter()
+        .filter(|availability| availability.category.id() == TexturesCategory.id())
+        .flat_map(|availability| &availability.items)
+        .filter_map(|item| {
+            let level = ForgeTextureQualityLevel::convert_from_i32(item.key.parse().ok()?)?;
+            Some((level, item.is_available()))
+        })
+        .collect()
+}
+
+// the tiers the ledger says Shears removed
+fn removed_texture_levels(ledger: &ShearLedger) -> BTreeSet<i32> {
+    ledger
+        .shears
+        .iter()
+        .flat_map(|entry| &entry.files)
+        .filter(|file| file.category == TexturesCategory.id())
+        .filter_map(|file| TexturesCategory::quality_level_of(&file.path))
+        .map(|level| level.convert_to_i32())
+        .collect()
+}
+
+// goes by the same rule as the texture check, so an advanced shear that dropped the lower tiers
+// isn't reported as broken. tiers missing below the base are fine only if Shears removed them
+fn texture_inconsistencies(
+    tiers: &[(ForgeTextureQualityLevel, bool)],
+    removed: &BTreeSet<i32>,
+) -> Vec<String> {
+    let mut inconsistencies: Vec<String> = TexturesCategory::find_tier_gap(tiers.iter().copied())
+        .map(|(gap, highest)| {
+            format!("{highest} textures are present but {gap} textures are missing.")
+        })
+        .into_iter()
+        .collect();
+
+    let below_base = tiers.iter().take_while(|&&(_, present)| !present);
+    if tiers.iter().any(|&(_, present)| present) {
+        inconsistencies.extend(
+            below_base
+                .filter(|(level, _)| !removed.contains(&level.convert_to_i32()))
+                .map(|(level, _)| {
+                    format!("{level} textures are missing, but Shears didn't remove them. Verify the game files.")
+                }),
+        );
+    }
+
+    inconsistencies
+}
+
+// combines what is missing from the install with what Shears left behind in it
+pub fn detect_shear_state(root: &Path, features: &ShearingFeaturesAvailability) -> ShearState {
+    let ledger = ShearLedger::load(root);
+    let mut state = ShearState {
+        redownload: DepotFileList::from_ledger(root, &ledger),
+        ledger,
+        highest_texture_level: features.highest_texture_level(),
+        ..ShearState::default()
+    };
+    let tiers = texture_tiers(features);
+    state.lowest_texture_level = tiers
+        .iter()
+        .find(|&&(_, present)| present)
+        .map(|&(level, _)| level);
+    let removed_textures = removed_texture_levels(&state.ledger);
+
+    // textures and videos ship with every install, everything else may legitimately be absent
+    for availability in features.categories.iter().filter(|availability| {
+        [TexturesCategory.id(), VideosCategory.id()].contains(&availability.category.id())
+    }) {
+        for item in availability
+            .items
+            .iter()
+            .filter(|item| !item.is_available())
+        {
+            // Low missing is a broken install rather than a shear, unless Shears removed it
+            if availability.category.id() == TexturesCategory.id()
+                && item.key == (ForgeTextureQualityLevel::Low as i32).to_string()
+                && !removed_textures.contains(&ForgeTextureQualityLevel::Low.convert_to_i32())
+            {
+                continue;
+            }
+            state.missing.push(item.label.clone());
+            state
+                .missing_categories
+                .insert(availability.category.id().to_owned());
+        }
+    }
+
+    state.inconsistencies = texture_inconsistencies(&tiers, &removed_textures);
+    state.has_texture_gaps = !state.inconsistencies.is_empty();
+
+    if let Ok(streaming_install) = StreamingInstall::load(root) {
+        state.streaming_install_version = streaming_install.shears_version();
+
+        // a file can be mapped to several chunks
+        let missing_files: BTreeSet<String> = streaming_install
+            .chunk_status(root)
+            .into_iter()
+            .flat_map(|chunk| chunk.files)
+            .filter(|&(_, exists)| !exists)
+            .map(|(file, _)| file)
+            .collect();
+        if !missing_files.is_empty() {
+            state.inconsistencies.push(format!(
+                "streaminginstall.ini still lists {} files that are missing, the game may try to stream them.",
+                missing_files.len()
+            ));
+        }
+    }
+
+    // the ledger says these were removed but they are all there
+    for category in [TexturesCategory.id(), VideosCategory.id()]
+        .into_iter()
+        .filter(|&id| !state.missing_categories.contains(id))
+        .filter_map(find_category)
+    {
+        if let Some(entry) = state
+            .ledger
+            .shears
+            .iter()
+            .rfind(|entry| entry.categories.contains(category.id()))
+        {
+            state.inconsistencies.push(format!(
+                "{} were removed by Shears on {}, but are complete again. The game files were probably verified since.",
+                category.name(),
+                format_timestamp(entry.date)
+            ));
+        }
+    }
+
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::{
+        categories::CategoryAvailability,
+        diskusage::DiskUsage,
+        ledger::{LedgerEntry, LedgerFile},
+    };
+
+    fn tier_file(level: usize) -> PathBuf {
+        PathBuf::from(format!("datapc64_merged_bnk_textures{level}.forge"))
+    }
+
+    // texture tiers from Low up, with whether each one is installed
+    fn textures(present: &[bool]) -> CategoryAvailability {
+        let mut items = TexturesCategory.known_items();
+        for (level, (item, &present)) in items.iter_mut().zip(present).enumerate() {
+            item.paths = vec![tier_file(level)];
+            if present {
+                item.size = DiskUsage {
+                    apparent: 1,
+                    reclaimable: 1,
+                    linked_files: 0,
+                };
+            }
+        }
+        CategoryAvailability {
+            category: &TexturesCategory,
+            items,
+        }
+    }
+
+    fn features(present: &[bool]) -> ShearingFeaturesAvailability {
+        ShearingFeaturesAvailability {
+            has_forge_files: true,
+            categories: vec![textures(present)],
+            ..Default::default()
+        }
+    }
+
+    fn record_removed_tiers(root: &Path, levels: &[usize]) {
+        let entry = LedgerEntry {
+            date: 1_738_346_400,
+            categories: BTreeSet::from([TexturesCategory.id().to_owned()]),
+            files: levels
+                .iter()
+                .map(|&level| LedgerFile {
+                    path: tier_file(level),
+                    category: TexturesCategory.id().to_owned(),
+                    size: 1,
+                    folder: false,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        ShearLedger::append(root, entry).expect("Failed to write the ledger");
+    }
+
+    #[test]
+    fn dropped_lower_tiers_are_a_shear() {
+        let install = tempfile::tempdir().expect("Failed to create the test install");
+
+        // what the texture check allows before shearing...
+        let before = textures(&[true; 5]);
+        let plan = TexturesCategory.plan(&before.items, &[false, false, true, true, true]);
+        assert!(TexturesCategory.check_plan(&before.items, &plan).is_ok());
+
+        // ...is not reported as broken afterwards
+        record_removed_tiers(install.path(), &[0, 1]);
+        let state =
+            detect_shear_state(install.path(), &features(&[false, false, true, true, true]));
+        assert!(
+            state.inconsistencies.is_empty(),
+            "{:?}",
+            state.inconsistencies
+        );
+        assert!(!state.has_texture_gaps);
+        assert!(
+            state.summary().starts_with("Sheared to High - Ultra on "),
+            "{}",
+            state.summary()
+        );
+    }
+
+    #[test]
+    fn missing_lower_tiers_shears_did_not_remove_are_reported() {
+        let install = tempfile::tempdir().expect("Failed to create the test install");
+
+        let state =
+            detect_shear_state(install.path(), &features(&[false, false, true, true, true]));
+        assert_eq!(
+            state.inconsistencies.len(),
+            2,
+            "{:?}",
+            state.inconsistencies
+        );
+        assert!(state.has_texture_gaps);
+    }
+
+    #[test]
+    fn gaps_between_tiers_are_reported() {
+        let install = tempfile::tempdir().expect("Failed to create the test install");
+
+        let before = textures(&[true; 5]);
+        let plan = TexturesCategory.plan(&before.items, &[true, false, true, false, false]);
+        assert!(TexturesCategory.check_plan(&before.items, &plan).is_err());
+
+        record_removed_tiers(install.path(), &[1]);
+        let state = detect_shear_state(
+            install.path(),
+            &features(&[true, false, true, false, false]),
+        );
+        assert_eq!(
+            state.inconsistencies,
+            vec!["High textures are present but Medium textures are missing.".to_owned()]
+        );
+    }
+}
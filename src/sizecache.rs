@@ -0,0 +1,321 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::{diskusage::DiskUsage, forge::read_forge, settings::get_config_dir};
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+enum ForgeCheck {
+    #[default]
+    Unchecked,
+    Valid,
+    Damaged(String),
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+struct CachedForge {
+    modified: u64, // nanoseconds since the unix epoch
+    len: u64,
+    #[serde(default)]
+    forge_check: ForgeCheck,
+}
+
+// what a folder holds itself, its subfolders are cached on their own
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+struct CachedFolder {
+    modified: u64, // nanoseconds since the unix epoch
+    files: DiskUsage,
+    subfolders: Vec<String>,
+}
+
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+struct CachedResults {
+    forges: BTreeMap<PathBuf, CachedForge>,
+    folders: BTreeMap<PathBuf, CachedFolder>,
+}
+
+// results from earlier analyses, so reopening an install only stats its folders. a forge is read
+// again once its mtime or length changes, a folder is listed again once its own mtime changes.
+// adding, removing or renaming a file changes that mtime, rewriting one in place doesn't, which is
+// why Refresh drops the folders of the install with `forget`
+#[derive(Debug, Default)]
+pub struct SizeCache {
+    results: Mutex<CachedResults>,
+}
+
+fn modified_nanos(metadata: &std::fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|duration| u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX))
+        .unwrap_or(0)
+}
+
+impl SizeCache {
+    fn get_path() -> PathBuf {
+        get_config_dir().join("SizeCache.toml")
+    }
+
+    pub fn load_or_default() -> Self {
+        let results = std::fs::read_to_string(Self::get_path())
+            .ok()
+            .and_then(|contents| toml::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        Self {
+            results: Mutex::new(results),
+        }
+    }
+
+    // entries of paths that are gone are dropped so the cache doesn't grow forever
+    pub fn save_to_file(&self) {
+        let Ok(mut results) = self.results.lock() else {
+            return;
+        };
+        results.forges.retain(|path, _| path.exists());
+        results.folders.retain(|path, _| path.exists());
+
+        let path = Self::get_path();
+        if let Some(parent) = path.parent()
+            && let Err(e) = std::fs::create_dir_all(parent)
+        {
+            log::error!("Failed to create folder: {e}");
+        }
+
+        match toml::to_string(&*results) {
+            Ok(contents) => {
+                if let Err(e) = std::fs::write(path, contents) {
+                    log::error!("Failed to save the size cache: {e}");
+                }
+            }
+            Err(e) => log::error!("Failed to serialize the size cache: {e}"),
+        }
+    }
+
+    // the next analysis lists every folder inside `root` again
+    pub fn forget(&self, root: &Path) {
+        if let Ok(mut results) = self.results.lock() {
+            results.folders.retain(|path, _| !path.starts_with(root));
+        }
+    }
+
+    // the cached entry if the forge is unchanged, otherwise a fresh one without a check
+    fn lookup_forge(&self, path: &Path, metadata: &std::fs::Metadata) -> CachedForge {
+        let fresh = CachedForge {
+            modified: modified_nanos(metadata),
+            len: metadata.len(),
+            forge_check: ForgeCheck::Unchecked,
+        };
+
+        let Ok(mut results) = self.results.lock() else {
+            return fresh;
+        };
+
+        results
+            .forges
+            .entry(path.to_path_buf())
+            .and_modify(|cached| {
+                if cached.modified != fresh.modified || cached.len != fresh.len {
+                    *cached = fresh.clone();
+                }
+            })
+            .or_insert_with(|| fresh.clone())
+            .clone()
+    }
+
+    // why the forge is damaged, if it is. parsing a forge is the slow part of an analysis
+    pub fn forge_error(&self, path: &Path) -> Option<String> {
+        let metadata = match std::fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) => return Some(e.to_string()),
+        };
+
+        match self.lookup_forge(path, &metadata).forge_check {
+            ForgeCheck::Valid => return None,
+            ForgeCheck::Damaged(reason) => return Some(reason),
+            ForgeCheck::Unchecked => {}
+        }
+
+        let forge_check = match read_forge(path) {
+            Ok(_) => ForgeCheck::Valid,
+            Err(e) => ForgeCheck::Damaged(e.to_string()),
+        };
+        if let Ok(mut results) = self.results.lock()
+            && let Some(cached) = results.forges.get_mut(path)
+        {
+            cached.forge_check = forge_check.clone();
+        }
+
+        match forge_check {
+            ForgeCheck::Damaged(reason) => Some(reason),
+            ForgeCheck::Valid | ForgeCheck::Unchecked => None,
+        }
+    }
+
+    // the folder as it is now, and whether every entry could be read
+    fn list_folder(
+        path: &Path,
+        modified: u64,
+        unreadable: &mut Vec<(PathBuf, String)>,
+    ) -> (CachedFolder, bool) {
+        let mut folder = CachedFolder {
+            modified,
+            files: DiskUsage::default(),
+            subfolders: Vec::new(),
+        };
+
+        let entries = match std::fs::read_dir(path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                unreadable.push((path.to_path_buf(), e.to_string()));
+                return (folder, false);
+            }
+        };
+
+        let mut complete = true;
+        for entry in entries {
+            let entry = entry.and_then(|entry| {
+                let metadata = std::fs::metadata(entry.path())?;
+                Ok((entry.file_name(), metadata))
+            });
+
+            match entry {
+                Ok((name, metadata)) if metadata.is_dir() => {
+                    folder.subfolders.push(name.to_string_lossy().into_owned());
+                }
+                Ok((_, metadata)) => folder.files = folder.files + DiskUsage::of_file(&metadata),
+                Err(e) => {
+                    unreadable.push((path.to_path_buf(), e.to_string()));
+                    complete = false;
+                }
+            }
+        }
+
+        (folder, complete)
+    }
+
+    // `diskusage::path_usage`, with every folder that didn't change since the last call taken from the cache
+    pub fn path_usage(&self, path: &Path, unreadable: &mut Vec<(PathBuf, String)>) -> DiskUsage {
+        let metadata = match std::fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                unreadable.push((path.to_path_buf(), e.to_string()));
+                return DiskUsage::default();
+            }
+        };
+
+        if !metadata.is_dir() {
+            return DiskUsage::of_file(&metadata);
+        }
+
+        let modified = modified_nanos(&metadata);
+        let cached = self.results.lock().ok().and_then(|results| {
+            results
+                .folders
+                .get(path)
+                .filter(|cached| cached.modified == modified)
+                .cloned()
+        });
+
+        let folder = cached.unwrap_or_else(|| {
+            let (folder, complete) = Self::list_folder(path, modified, unreadable);
+            // folders with unreadable entries are listed every time, so the errors keep showing up
+            if complete && let Ok(mut results) = self.results.lock() {
+                results.folders.insert(path.to_path_buf(), folder.clone());
+            }
+            folder
+        });
+
+        folder.subfolders.iter().fold(folder.files, |usage, name| {
+            usage + self.path_usage(&path.join(name), unreadable)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_install() -> tempfile::TempDir {
+        let install = tempfile::tempdir().expect("Failed to create the test install");
+        std::fs::create_dir_all(install.path().join("videos"))
+            .expect("Failed to create the test install");
+        std::fs::write(install.path().join("DataPC64.forge"), [0; 10])
+            .expect("Failed to create the test install");
+        std::fs::write(install.path().join("videos").join("intro.bik"), [0; 20])
+            .expect("Failed to create the test install");
+        install
+    }
+
+    fn apparent(cache: &SizeCache, path: &Path) -> u64 {
+        let mut unreadable = Vec::new();
+        let usage = cache.path_usage(path, &mut unreadable);
+        assert!(unreadable.is_empty(), "{unreadable:?}");
+        usage.apparent
+    }
+
+    #[test]
+    fn matches_an_uncached_walk() {
+        let install = test_install();
+        let cache = SizeCache::default();
+
+        let uncached = crate::diskusage::path_usage(install.path(), &mut Vec::new());
+        assert_eq!(cache.path_usage(install.path(), &mut Vec::new()), uncached);
+        assert_eq!(cache.path_usage(install.path(), &mut Vec::new()), uncached);
+    }
+
+    #[test]
+    fn reuses_unchanged_folders_until_forgotten() {
+        let install = test_install();
+        let cache = SizeCache::default();
+        assert_eq!(apparent(&cache, install.path()), 30);
+
+        // rewriting a file in place leaves the mtime of its folder alone
+        std::fs::write(install.path().join("videos").join("intro.bik"), [0; 50])
+            .expect("Failed to rewrite the test file");
+        assert_eq!(apparent(&cache, install.path()), 30);
+
+        cache.forget(install.path());
+        assert_eq!(apparent(&cache, install.path()), 60);
+    }
+
+    #[test]
+    fn survives_saving() {
+        let install = test_install();
+        let cache = SizeCache::default();
+        cache.path_usage(install.path(), &mut Vec::new());
+
+        let contents = cache
+            .results
+            .lock()
+            .map(|results| toml::to_string(&*results))
+            .expect("The cache isn't poisoned")
+            .expect("Failed to serialize the cache");
+        let loaded: CachedResults = toml::from_str(&contents).expect("Failed to parse the cache");
+        assert_eq!(loaded.folders.len(), 2);
+    }
+
+    // directories can't be opened as files on Windows
+    #[cfg(unix)]
+    #[test]
+    fn lists_changed_folders_again() {
+        let install = test_install();
+        let cache = SizeCache::default();
+        assert_eq!(apparent(&cache, install.path()), 30);
+
+        let videos = install.path().join("videos");
+        std::fs::write(videos.join("outro.bik"), [0; 5]).expect("Failed to add the test file");
+        // the clock behind mtimes can be coarser than the time between the two measurements
+        std::fs::File::open(&videos)
+            .and_then(|folder| {
+                folder.set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1))
+            })
+            .expect("Failed to touch the test folder");
+
+        assert_eq!(apparent(&cache, install.path()), 35);
+    }
+}
@@ -0,0 +1,393 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+pub const STREAMING_INSTALL_FILE_NAME: &str = "streaminginstall.ini";
+pub const SHEARS_HEADER: &str = "; Written by Shears";
+
+const MISSION_TO_CHUNK: &str = "MissionToChunk";
+const FILE_TO_CHUNK: &str = "FileToChunk";
+
+#[derive(Clone, Debug)]
+enum Line {
+    Entry { key: String, value: String },
+    Other(String), // comments, blank lines and anything we don't understand are kept as they are
+}
+
+#[derive(Clone, Debug)]
+struct Section {
+    name: String,
+    lines: Vec<Line>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StreamingInstall {
+    preamble: Vec<String>,
+    sections: Vec<Section>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ChunkStatus {
+    pub chunk: String,
+    pub files: Vec<(String, bool)>, // file name, whether it exists in the install
+    pub missions: Vec<String>,
+}
+
+impl ChunkStatus {
+    pub fn is_missing(&self) -> bool {
+        !self.files.is_empty() && self.files.iter().all(|&(_, exists)| !exists)
+    }
+}
+
+fn normalize_key(key: &str) -> String {
+    key.trim().replace('\\', "/").to_ascii_lowercase()
+}
+
+fn split_chunks(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|chunk| !chunk.is_empty())
+}
+
+impl StreamingInstall {
+    pub fn parse(contents: &str) -> Self {
+        let mut parsed = Self::default();
+
+        for raw_line in contents.lines() {
+            let trimmed = raw_line.trim();
+
+            if let Some(name) = trimmed
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+            {
+                parsed.sections.push(Section {
+                    name: name.trim().to_owned(),
+                    lines: Vec::new(),
+                });
+                continue;
+            }
+
+            let line = match trimmed.split_once('=') {
+                Some((key, value)) if !trimmed.starts_with(';') && !trimmed.starts_with('#') => {
+                    Line::Entry {
+                        key: key.trim().to_owned(),
+                        value: value.trim().to_owned(),
+                    }
+                }
+                _ => Line::Other(raw_line.to_owned()),
+            };
+
+            match parsed.sections.last_mut() {
+                Some(section) => section.lines.push(line),
+                None => parsed.preamble.push(raw_line.to_owned()),
+            }
+        }
+
+        parsed
+    }
+
+    pub fn load(folder: &Path) -> std::io::Result<Self> {
+        std::fs::read_to_string(folder.join(STREAMING_INSTALL_FILE_NAME))
+            .map(|contents| Self::parse(&contents))
+    }
+
+    // the version in the header Shears writes, none if the file is still the game's own
+    pub fn shears_version(&self) -> Option<String> {
+        self.preamble.iter().find_map(|line| {
+            line.trim_start()
+                .strip_prefix(SHEARS_HEADER)
+                .map(|version| version.trim().to_owned())
+        })
+    }
+
+    pub fn to_ini_string(&self) -> String {
+        let mut output = format!("{SHEARS_HEADER} {}\n", env!("CARGO_PKG_VERSION"));
+
+        for line in &self.preamble {
+            if !line.trim_start().starts_with(SHEARS_HEADER) {
+                output.push_str(line);
+                output.push('\n');
+            }
+        }
+
+        for section in &self.sections {
+            output.push('[');
+            output.push_str(&section.name);
+            output.push_str("]\n");
+
+            for line in &section.lines {
+                match line {
+                    Line::Entry { key, value } => {
+                        output.push_str(key);
+                        output.push('=');
+                        output.push_str(value);
+                    }
+                    Line::Other(other) => output.push_str(other),
+                }
+                output.push('\n');
+            }
+        }
+
+        output
+    }
+
+    fn section(&self, name: &str) -> Option<&Section> {
+        self.sections
+            .iter()
+            .find(|section| section.name.eq_ignore_ascii_case(name))
+    }
+
+    fn section_mut(&mut self, name: &str) -> Option<&mut Section> {
+        self.sections
+            .iter_mut()
+            .find(|section| section.name.eq_ignore_ascii_case(name))
+    }
+
+    fn ensure_section(&mut self, name: &str) {
+        if self.section(name).is_none() {
+            self.sections.push(Section {
+                name: name.to_owned(),
+                lines: Vec::new(),
+            });
+        }
+    }
+
+    fn entries<'a>(&'a self, name: &str) -> impl Iterator<Item = (&'a str, &'a str)> {
+        self.section(name)
+            .into_iter()
+            .flat_map(|section| section.lines.iter())
+            .filter_map(|line| match line {
+                Line::Entry { key, value } => Some((key.as_str(), value.as_str())),
+                Line::Other(_) => None,
+            })
+    }
+
+    // drops the file mappings of removed files, then the chunks that no longer have any file
+    // left get dropped from the mission mappings too. everything else stays untouched
+    pub fn remove_files(&mut self, removed: &[String]) -> BTreeSet<String> {
+        let is_removed = |key: &str| {
+            let key = normalize_key(key);
+            removed.iter().any(|removed| {
+                // the file itself, a file inside a removed folder, or a key without the `.forge` extension
+                key == *removed
+                    || key
+                        .strip_prefix(removed.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+                    || removed
+                        .strip_suffix(".forge")
+                        .is_some_and(|stem| key == stem)
+            })
+        };
+
+        let mut chunk_files: BTreeMap<String, (usize, usize)> = BTreeMap::new(); // total, removed
+        for (key, value) in self.entries(FILE_TO_CHUNK) {
+            let removed = is_removed(key);
+            for chunk in split_chunks(value) {
+                let counts = chunk_files.entry(chunk.to_owned()).or_default();
+                counts.0 += 1;
+                counts.1 += usize::from(removed);
+            }
+        }
+
+        let deleted_chunks: BTreeSet<String> = chunk_files
+            .into_iter()
+            .filter(|&(_, (total, removed))| total == removed)
+            .map(|(chunk, _)| chunk)
+            .collect();
+
+        if let Some(section) = self.section_mut(FILE_TO_CHUNK) {
+            section.lines.retain(|line| match line {
+                Line::Entry { key, .. } => !is_removed(key),
+                Line::Other(_) => true,
+            });
+        }
+
+        if let Some(section) = self.section_mut(MISSION_TO_CHUNK) {
+            section.lines.retain_mut(|line| {
+                let Line::Entry { value, .. } = line else {
+                    return true;
+                };
+
+                let kept: Vec<&str> = split_chunks(value)
+                    .filter(|chunk| !deleted_chunks.contains(*chunk))
+                    .collect();
+                if kept.is_empty() {
+                    return false;
+                }
+
+                *value = kept.join(",");
+                true
+            });
+        }
+
+        self.ensure_section(MISSION_TO_CHUNK);
+        self.ensure_section(FILE_TO_CHUNK);
+
+        deleted_chunks
+    }
+
+    pub fn chunk_status(&self, folder: &Path) -> Vec<ChunkStatus> {
+        let mut chunks: BTreeMap<String, ChunkStatus> = BTreeMap::new();
+
+        for (key, value) in self.entries(FILE_TO_CHUNK) {
+            let path = folder.join(key.replace('\\', "/"));
+            let exists = path.exists() || path.with_extension("forge").exists();
+
+            for chunk in split_chunks(value) {
+                let status = chunks.entry(chunk.to_owned()).or_default();
+                status.files.push((key.to_owned(), exists));
+            }
+        }
+
+        for (mission, value) in self.entries(MISSION_TO_CHUNK) {
+            for chunk in split_chunks(value) {
+                let status = chunks.entry(chunk.to_owned()).or_default();
+                status.missions.push(mission.to_owned());
+            }
+        }
+
+        chunks
+            .into_iter()
+            .map(|(chunk, status)| ChunkStatus { chunk, ..status })
+            .collect()
+    }
+}
+
+// rewrites streaminginstall.ini without the mappings of the removed files. the journal snapshots
+// the file beforehand, that snapshot is what a rollback restores
+pub fn update_streaminginstall(folder: &Path, removed_paths: &[PathBuf]) -> std::io::Result<()> {
+    let streaming_install_path = folder.join(STREAMING_INSTALL_FILE_NAME);
+
+    let mut streaming_install = if streaming_install_path.exists() {
+        StreamingInstall::load(folder)?
+    } else {
+        StreamingInstall::default()
+    };
+
+    let removed: Vec<String> = removed_paths
+        .iter()
+        .filter_map(|path| path.strip_prefix(folder).ok())
+        .map(|relative| normalize_key(&relative.to_string_lossy()))
+        .collect();
+
+    let deleted_chunks = streaming_install.remove_files(&removed);
+    if !deleted_chunks.is_empty() {
+        log::info!("Removed chunks from streaminginstall.ini: {deleted_chunks:?}");
+    }
+
+    std::fs::write(streaming_install_path, streaming_install.to_ini_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "; mappings for the streamed install
+[MissionToChunk]
+menu=1,2
+ultra=3, 4
+only_ultra=4
+
+[FileToChunk]
+datapc64_main.forge=1
+DataPC64_Textures3.forge=2,3
+datapc64_textures4=3
+Videos\\intro.bik=2
+datapc64_textures4_extra.forge=4
+";
+
+    #[test]
+    fn round_trips_unknown_lines() {
+        let parsed = StreamingInstall::parse(SAMPLE);
+        assert_eq!(parsed.shears_version(), None);
+
+        let written = parsed.to_ini_string();
+        assert_eq!(
+            written,
+            format!("{SHEARS_HEADER} {}\n{SAMPLE}", env!("CARGO_PKG_VERSION"))
+        );
+
+        let reparsed = StreamingInstall::parse(&written);
+        assert_eq!(
+            reparsed.shears_version().as_deref(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+        assert_eq!(
+            reparsed.to_ini_string(),
+            written,
+            "the header is written once"
+        );
+    }
+
+    #[test]
+    fn removes_files_and_emptied_chunks() {
+        let mut parsed = StreamingInstall::parse(SAMPLE);
+        let removed = [
+            "datapc64_textures4.forge".to_owned(),
+            "datapc64_textures4_extra.forge".to_owned(),
+            "videos".to_owned(),
+        ];
+
+        let deleted_chunks = parsed.remove_files(&removed);
+        assert_eq!(deleted_chunks, BTreeSet::from(["4".to_owned()]));
+
+        let written = parsed.to_ini_string();
+        let body = written
+            .split_once('\n')
+            .map(|(_, body)| body)
+            .unwrap_or_default();
+        assert_eq!(
+            body,
+            "; mappings for the streamed install
+[MissionToChunk]
+menu=1,2
+ultra=3
+
+[FileToChunk]
+datapc64_main.forge=1
+DataPC64_Textures3.forge=2,3
+"
+        );
+    }
+
+    #[test]
+    fn keeps_files_sharing_a_prefix() {
+        let mut parsed = StreamingInstall::parse(SAMPLE);
+        parsed.remove_files(&["datapc64_textures4_extra.forge".to_owned()]);
+
+        let kept: Vec<&str> = parsed.entries(FILE_TO_CHUNK).map(|(key, _)| key).collect();
+        assert_eq!(
+            kept,
+            vec![
+                "datapc64_main.forge",
+                "DataPC64_Textures3.forge",
+                "datapc64_textures4",
+                "Videos\\intro.bik",
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_files_with_a_removed_name_as_prefix() {
+        let mut parsed =
+            StreamingInstall::parse("[FileToChunk]\nvideos.bak=1\nvideos/intro.bik=2\n");
+        parsed.remove_files(&["videos".to_owned()]);
+
+        let kept: Vec<&str> = parsed.entries(FILE_TO_CHUNK).map(|(key, _)| key).collect();
+        assert_eq!(kept, vec!["videos.bak"]);
+    }
+
+    #[test]
+    fn adds_missing_sections() {
+        let mut parsed = StreamingInstall::parse("");
+        parsed.remove_files(&[]);
+
+        let written = parsed.to_ini_string();
+        assert!(
+            written.contains("[MissionToChunk]\n[FileToChunk]\n"),
+            "{written}"
+        );
+    }
+}
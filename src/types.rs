@@ -1,4 +1,18 @@
-use crate::scan::scan_recursive;
+use crate::{
+    batch::BatchInstallResult,
+    categories::{CategoryAvailability, ShearCategory as _, ShearSelection, TexturesCategory},
+    compare::InstallComparison,
+    diskusage::DiskUsage,
+    forge::ForgeFile,
+    games::GameDefinition,
+    gamesettings::GameSettingsFile,
+    journal::{Journal, JournalOutcome},
+    planner::{CutId, SpaceSurvey},
+    progress::JobProgress,
+    scan::scan_recursive,
+    shearstate::ShearState,
+    streaminginstall::ChunkStatus,
+};
 
 #[derive(Debug)]
 pub struct ShearsScanFolderState {
@@ -24,7 +38,11 @@ impl Default for ShearsScanFolderState {
 }
 
 impl ShearsScanFolderState {
-    pub fn start_scan_thread(&mut self, drive: std::path::PathBuf) {
+    pub fn start_scan_thread(
+        &mut self,
+        drive: std::path::PathBuf,
+        definitions: Vec<GameDefinition>,
+    ) {
         self.stop_flag
             .store(false, std::sync::atomic::Ordering::Relaxed);
 
@@ -33,7 +51,7 @@ impl ShearsScanFolderState {
         self.restart_timer();
         self.thread_handle = Some(std::thread::spawn(move || {
             let mut found_paths = Vec::new();
-            scan_recursive(&drive, &mut found_paths, &flag_clone);
+            scan_recursive(&drive, &definitions, &mut found_paths, &flag_clone);
 
             if flag_clone.load(std::sync::atomic::Ordering::Relaxed) {
                 log::info!("Scan was cancelled early.");
@@ -56,7 +74,7 @@ impl ShearsScanFolderState {
         self.scan_length = Some(elapsed);
     }
 
-    fn fmt_time(seconds: u64) -> String {
+    pub fn fmt_time(seconds: u64) -> String {
         let hours = seconds / 3600;
         let minutes = (seconds % 3600) / 60;
         let secs = seconds % 60;
@@ -82,16 +100,179 @@ impl ShearsScanFolderState {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShearsJobKind {
+    Analysis,
+    Shear,
+    Survey,
+    Compare,
+    InspectForges,
+}
+
+#[derive(Debug)]
+pub enum ShearsJobResult {
+    Analysis {
+        folder: std::path::PathBuf,
+        features_availability: ShearingFeaturesAvailability,
+        game_settings: Vec<GameSettingsFile>,
+        shear_state: ShearState,
+    },
+    Shear {
+        folder: std::path::PathBuf,
+        result: Result<JournalOutcome, String>,
+        // free space on the drive before and after, what was actually reclaimed
+        available_space: Option<(u64, u64)>,
+    },
+    Batch {
+        results: Vec<BatchInstallResult>,
+    },
+    Survey {
+        survey: SpaceSurvey,
+    },
+    Compare {
+        comparison: InstallComparison,
+    },
+    InspectForges {
+        forges: Vec<(std::path::PathBuf, Result<ForgeFile, String>)>,
+    },
+}
+
+// analysis and shearing run on a worker thread so slow drives don't freeze the window
+#[derive(Debug)]
+pub struct ShearsJobState {
+    pub kind: Option<ShearsJobKind>,
+    pub thread_handle: Option<std::thread::JoinHandle<ShearsJobResult>>,
+    pub progress: std::sync::Arc<JobProgress>,
+    timer_start: std::time::Instant,
+}
+
+impl Default for ShearsJobState {
+    fn default() -> Self {
+        Self {
+            kind: None,
+            thread_handle: None,
+            progress: std::sync::Arc::new(JobProgress::default()),
+            timer_start: std::time::Instant::now(),
+        }
+    }
+}
+
+impl ShearsJobState {
+    // a job that is still running is cancelled and left to finish on its own
+    pub fn start_job(
+        &mut self,
+        kind: ShearsJobKind,
+        job: impl FnOnce(&JobProgress) -> ShearsJobResult + Send + 'static,
+    ) {
+        self.progress.cancel();
+
+        let progress = std::sync::Arc::new(JobProgress::default());
+        let progress_clone = progress.clone();
+
+        self.kind = Some(kind);
+        self.progress = progress;
+        self.timer_start = std::time::Instant::now();
+        self.thread_handle = Some(std::thread::spawn(move || job(&progress_clone)));
+    }
+
+    pub fn is_running(&self, kind: ShearsJobKind) -> bool {
+        self.kind == Some(kind) && self.thread_handle.is_some()
+    }
+
+    pub fn take_finished(&mut self) -> Option<ShearsJobResult> {
+        if !self.thread_handle.as_ref().is_some_and(|h| h.is_finished()) {
+            return None;
+        }
+
+        let handle = self.thread_handle.take()?;
+        self.kind = None;
+
+        match handle.join() {
+            Ok(result) => Some(result),
+            Err(e) => {
+                log::error!("Job thread panicked: {e:?}");
+                None
+            }
+        }
+    }
+
+    // stops waiting for the job, it exits at its next cancellation check
+    pub fn abandon(&mut self) {
+        self.progress.cancel();
+        self.thread_handle = None;
+        self.kind = None;
+    }
+
+    pub fn ongoing_timer_elapsed(&self) -> String {
+        ShearsScanFolderState::fmt_time(self.timer_start.elapsed().as_secs())
+    }
+}
+
+#[derive(Debug)]
+pub struct ShearsBatchState {
+    pub selected_folders: std::collections::BTreeSet<std::path::PathBuf>,
+    pub selection: ShearSelection,
+    pub profile: Option<String>, // the profile last applied to `selection`
+    pub report: Vec<BatchInstallResult>,
+    pub return_page: ShearsPage, // where the report leads back to
+}
+
+impl Default for ShearsBatchState {
+    fn default() -> Self {
+        Self {
+            selected_folders: std::collections::BTreeSet::new(),
+            selection: ShearSelection::default(),
+            profile: None,
+            report: Vec::new(),
+            return_page: ShearsPage::DiskScanComplete,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ShearsCompareState {
+    pub a: Option<std::path::PathBuf>,
+    pub b: Option<std::path::PathBuf>,
+    pub compare_contents: bool, // hashes files of the same size, reads both installs in full
+    pub comparison: Option<InstallComparison>,
+}
+
+#[derive(Debug)]
+pub struct ShearsPlannerState {
+    pub keep_free: bool, // a minimum of free space on one drive instead of a fixed amount
+    pub amount_gb: f64,
+    pub mount_point: Option<std::path::PathBuf>,
+    pub minimum_free_gb: f64,
+    pub survey: Option<SpaceSurvey>,
+    pub excluded: std::collections::BTreeSet<CutId>,
+}
+
+impl Default for ShearsPlannerState {
+    fn default() -> Self {
+        Self {
+            keep_free: false,
+            amount_gb: 100.0,
+            mount_point: None,
+            minimum_free_gb: 100.0,
+            survey: None,
+            excluded: std::collections::BTreeSet::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct ShearsUiState {
     page: ShearsPage,
     last_page: ShearsPage,
 
-    pub checkbox_textures: [bool; ForgeTextureQualityLevel::COUNT],
-    pub checkbox_videos: bool,
-    pub checkbox_events: bool,
+    // one list of "keep" checkboxes per category, in the same order as the analyzed categories
+    pub checkboxes: Vec<Vec<bool>>,
 
-    pub label_possible_space_saved: u64,
+    pub label_possible_space_saved: DiskUsage,
+    pub damage_acknowledged: bool,
+    pub plan_error: Option<String>,
+    pub profile_name: String, // name for the next profile saved from the current selection
+    pub applied_profile: Option<String>, // the profile the current selection started from
 
     pub modals: [bool; ShearsModals::COUNT],
 }
@@ -102,11 +283,13 @@ impl Default for ShearsUiState {
             page: ShearsPage::MainPage,
             last_page: ShearsPage::MainPage,
 
-            checkbox_textures: [true; ForgeTextureQualityLevel::COUNT],
-            checkbox_videos: true,
-            checkbox_events: true,
+            checkboxes: Vec::new(),
 
-            label_possible_space_saved: 0,
+            label_possible_space_saved: DiskUsage::default(),
+            damage_acknowledged: false,
+            plan_error: None,
+            profile_name: String::new(),
+            applied_profile: None,
             modals: [false; ShearsModals::COUNT],
         }
     }
@@ -129,6 +312,11 @@ impl ShearsUiState {
         self.last_page = ShearsPage::MainPage;
     }
 
+    // swaps the current page but keeps where "Back" leads
+    pub fn replace_page(&mut self, new_page: ShearsPage) {
+        self.page = new_page;
+    }
+
     pub fn go_back(&mut self) {
         self.page = self.last_page;
         self.last_page = ShearsPage::MainPage;
@@ -142,20 +330,13 @@ impl ShearsUiState {
         self.last_page
     }
 
-    pub fn get_texture_checkbox(self, quality_level: usize) -> bool {
-        *self
-            .checkbox_textures
-            .get(quality_level)
-            .expect("ShearsUiState.get_texture_checkbox: Out of bounds error")
+    pub fn get_category_checkboxes_mut(&mut self, category_index: usize) -> &mut Vec<bool> {
+        self.checkboxes
+            .get_mut(category_index)
+            .expect("ShearsUiState.get_category_checkboxes_mut: Out of bounds error")
     }
 
-    pub fn get_texture_checkbox_mut(&mut self, quality_level: usize) -> &mut bool {
-        self.checkbox_textures
-            .get_mut(quality_level)
-            .expect("ShearsUiState.get_texture_checkbox_mut: Out of bounds error")
-    }
-
-    pub fn get_modal(self, modal_index: usize) -> bool {
+    pub fn get_modal(&self, modal_index: usize) -> bool {
         *self
             .modals
             .get(modal_index)
@@ -172,30 +353,57 @@ impl ShearsUiState {
 #[derive(Default, Clone, Debug)]
 pub struct ShearsFolderState {
     pub siege_path: Option<std::path::PathBuf>,
+    pub game: GameDefinition,
+    pub game_settings: Vec<GameSettingsFile>,
+    pub interrupted_journal: Option<Journal>,
     pub features_availability: ShearingFeaturesAvailability,
+    pub shear_state: ShearState,
+}
+
+#[derive(Default, Debug)]
+pub struct ShearsForgeInspectorState {
+    pub forges: Vec<(std::path::PathBuf, Result<ForgeFile, String>)>,
+    pub selected: Option<usize>,
+}
+
+#[derive(Debug)]
+pub struct ShearsStreamingInstallState {
+    pub chunks: Result<Vec<ChunkStatus>, String>,
+    pub shears_version: Option<String>, // set once Shears rewrote the file
+}
+
+impl Default for ShearsStreamingInstallState {
+    fn default() -> Self {
+        Self {
+            chunks: Ok(Vec::new()),
+            shears_version: None,
+        }
+    }
 }
 
-#[derive(Default, Clone, Copy, Debug)]
+#[derive(Default, Clone, Debug)]
 pub struct ShearingFeaturesAvailability {
     pub has_forge_files: bool,
+    pub install_size: DiskUsage,
+    pub damaged_forges: Vec<(std::path::PathBuf, String)>,
 
-    pub textures: [(bool, u64); ForgeTextureQualityLevel::COUNT],
-    pub videos: (bool, u64),
-    pub events: (bool, u64),
+    pub categories: Vec<CategoryAvailability>,
 }
 
 impl ShearingFeaturesAvailability {
-    pub fn get_texture(self, quality_level: usize) -> (bool, u64) {
-        *self
-            .textures
-            .get(quality_level)
-            .expect("ShearingFeaturesAvailability.get_texture: Out of bounds error")
-    }
-
-    pub fn get_texture_mut(&mut self, quality_level: usize) -> &mut (bool, u64) {
-        self.textures
-            .get_mut(quality_level)
-            .expect("ShearingFeaturesAvailability.get_texture_mut: Out of bounds error")
+    pub fn highest_texture_level(&self) -> Option<ForgeTextureQualityLevel> {
+        let textures = self
+            .categories
+            .iter()
+            .find(|availability| availability.category.id() == TexturesCategory.id())?;
+
+        textures
+            .items
+            .iter()
+            .filter(|item| item.is_available())
+            .filter_map(|item| item.key.parse().ok())
+            .filter_map(ForgeTextureQualityLevel::convert_from_i32)
+            .max_by_key(ForgeTextureQualityLevel::convert_to_i32)
     }
 }
 
@@ -235,8 +443,6 @@ impl std::fmt::Display for ForgeTextureQualityLevel {
 }
 
 impl ForgeTextureQualityLevel {
-    pub const COUNT: usize = 5;
-
     pub fn convert_from_i32(texture_level: i32) -> Option<Self> {
         match texture_level {
             0 => Some(Self::Low),
@@ -266,16 +472,25 @@ pub enum ShearsPage {
     DiskScanSelect,
     DiskScanInProgress,
     DiskScanComplete,
+    ForgeInspector,
+    StreamingInstallViewer,
+    JobInProgress,
+    MyInstalls,
+    BatchSetup,
+    BatchReport,
+    SpacePlanner,
+    CompareInstalls,
 }
 
 #[derive(Clone, Copy, Debug)]
 pub enum ShearsModals {
     About = 0,
     Settings,
+    InterruptedShear,
 }
 
 impl ShearsModals {
     pub const START: Self = Self::About;
-    pub const END: Self = Self::Settings;
-    pub const COUNT: usize = 2;
+    pub const END: Self = Self::InterruptedShear;
+    pub const COUNT: usize = 3;
 }